@@ -1,39 +1,187 @@
 #![cfg_attr(not(feature = "std"), no_std)]
-#![feature(type_ascription)]
 
 use ink_lang as ink;
 
 // subsa smart contract
 #[ink::contract]
 mod subsa {
-    use ink_storage::Mapping;
+    use ink_prelude::{string::String, vec::Vec};
+    use ink_storage::{
+        traits::{PackedLayout, SpreadAllocate, SpreadLayout},
+        Mapping,
+    };
 
     use scale::{Decode, Encode};
 
     pub type AssetId = AccountId;
 
+    /// The `Event` enum generated for the contract's events.
+    type Event = <Subsa as ink_lang::reflect::ContractEventBase>::Type;
+
+    /// Maximum number of approval changes kept per owner/spender pair.
+    /// Note: once the limit is reached, the oldest entries are overwritten.
+    const ALLOWANCE_HISTORY_LIMIT: u32 = 16;
+
+    /// Maximum number of entries a batch message accepts.
+    /// Note: keeps every batch within the block gas limit.
+    const MAX_BATCH: usize = 128;
+
+    /// Maximum length in bytes of a transfer note.
+    const MAX_NOTE_LEN: usize = 1024;
+
+    /// Version of the contract's message surface.
+    /// Note: bumped whenever a message is added, removed or changes its signature.
+    pub const CONTRACT_VERSION: u32 = 35;
+
+    /// Feature ids accepted by `supports_feature`.
+    pub const FEATURE_ESCROW: u32 = 0;
+    pub const FEATURE_FEES: u32 = 1;
+    pub const FEATURE_KYC: u32 = 2;
+    pub const FEATURE_SNAPSHOTS: u32 = 3;
+    pub const FEATURE_REGISTRY: u32 = 4;
+    pub const FEATURE_RENAMING: u32 = 5;
+    pub const FEATURE_MULTI_APPROVAL: u32 = 6;
+
+    /// Maximum number of privileged actions kept in the audit log.
+    /// Note: once the limit is reached, the oldest entries are overwritten.
+    const AUDIT_LOG_LIMIT: u32 = 32;
+
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
     /// to add new static storage fields to your contract.
     #[ink(storage)]
+    #[derive(SpreadAllocate)]
     pub struct Subsa {
         // immutable asset params
         creator: AccountId,
+        asset_id: Option<AssetId>,
         asset_name: String,
         unit_name: String,
         total: Balance,
+        // issued supply, the initial supply plus minted minus burned tokens
+        issued: Balance,
+        // running sum of all balances, pending claims and assignments
+        sum_balances: Balance,
+        // lifetime issued supply, burns don't reduce it
+        minted: Balance,
+        // cap on the lifetime issued supply, fixed at creation
+        max_supply: Option<Balance>,
         decimals: u32,
         default_frozen: bool,
+        // whether accounts can be frozen, fixed at creation
+        freezable: bool,
+        // smallest non-zero balance, fixed at creation
+        min_balance: Balance,
         url: String,
         metadata_hash: [u8; 4],
         // mutable asset params
         manager_id: AccountId,
+        // manager proposed by the current manager, until it accepts
+        pending_manager: Option<AccountId>,
         reserve_id: AccountId,
         freeze_id: AccountId,
         clawback_id: AccountId,
         balances: Mapping<AccountId, Balance>,
         accounts_opted_in: Mapping<AccountId, bool>,
+        opted_in_count: u32,
+        holder_count: u32,
+        // index of the opted in accounts, for enumeration
+        holder_ids: Mapping<u32, AccountId>,
+        holder_positions: Mapping<AccountId, u32>,
+        opt_in_ts: Mapping<AccountId, Timestamp>,
+        max_holders: Option<u32>,
+        // audit log
+        audit_entries: Mapping<u32, AuditEntry>,
+        audit_count: u32,
         frozen_holders: Mapping<AccountId, bool>,
+        freeze_exempt: Mapping<AccountId, bool>,
+        freeze_default_override: Mapping<AccountId, bool>,
+        freeze_reasons: Mapping<AccountId, u8>,
+        total_frozen: Balance,
+        // large transfer confirmation
+        large_transfer_threshold: Option<Balance>,
+        large_transfer_window: BlockNumber,
+        finality_delay: BlockNumber,
+        pending_large_transfers: Mapping<AccountId, (AccountId, Balance, BlockNumber, BlockNumber)>,
+        // allowances
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+        allowance_history: Mapping<(AccountId, AccountId, u32), (BlockNumber, Balance)>,
+        allowance_history_len: Mapping<(AccountId, AccountId), u32>,
+        // incident handling
+        paused: bool,
+        emergency_exit_enabled: bool,
+        // clawback
+        strict_clawback: bool,
+        clawback_renounced: bool,
+        // renounced authorities
+        freeze_renounced: bool,
+        management_renounced: bool,
+        reserve_renounced: bool,
+        // event schema
+        compact_events: bool,
+        // registry mode, opt-ins and freezes without token movements
+        registry_mode: bool,
+        // contract allowed to act in place of every role
+        controller: Option<AccountId>,
+        // accounts granted a role in addition to its authority address
+        role_grants: Mapping<(RoleKind, AccountId), bool>,
+        // multi-authority approval of modify and destroy
+        co_managers: Vec<AccountId>,
+        approval_threshold: u32,
+        proposal_count: u32,
+        proposals: Mapping<u32, PendingAction>,
+        approvals: Mapping<(u32, AccountId), bool>,
+        approval_counts: Mapping<u32, u32>,
+        // blocks a proposal must wait before it executes, and when each was proposed
+        reconfig_delay: BlockNumber,
+        proposal_blocks: Mapping<u32, BlockNumber>,
+        // whether the manager can rename the asset
+        names_mutable: bool,
+        // supply locked against minting and burning
+        supply_locked: bool,
+        // escrow of transfers to accounts that haven't opted in
+        allow_escrow: bool,
+        pending: Mapping<AccountId, Balance>,
+        pending_since: Mapping<AccountId, Timestamp>,
+        escrow_deadline: Timestamp,
+        // pull payments, assigned by sender and receiver
+        assignments: Mapping<(AccountId, AccountId), Balance>,
+        // balance snapshots
+        snapshot_count: u32,
+        snapshot_blocks: Mapping<u32, BlockNumber>,
+        snapshot_supplies: Mapping<u32, Balance>,
+        // native currency dividends, as (snapshot id, amount), and their claims
+        dividend_count: u32,
+        dividends: Mapping<u32, (u32, Balance)>,
+        dividend_claims: Mapping<(u32, AccountId), bool>,
+        // per-account balance checkpoints, as (snapshot id, balance) in snapshot order
+        balance_checkpoints: Mapping<(AccountId, u32), (u32, Balance)>,
+        checkpoint_counts: Mapping<AccountId, u32>,
+        // account activity
+        last_activity: Mapping<AccountId, BlockNumber>,
+        net_flows: Mapping<AccountId, (Balance, Balance)>,
+        received_totals: Mapping<AccountId, Balance>,
+        // distribution payouts
+        payout_addresses: Mapping<AccountId, AccountId>,
+        top_holder: AccountId,
+        // reserve
+        min_reserve: Balance,
+        // lifetime supply returned to the reserve through redeem
+        redeemed_total: Balance,
+        // transfer cooldown
+        transfer_cooldown: Timestamp,
+        last_transfer_ts: Mapping<AccountId, Timestamp>,
+        // transfer hook
+        transfer_hook: Option<AccountId>,
+        hook_must_succeed: bool,
+        // transfer fee
+        fee_start_bps: u16,
+        fee_floor_bps: u16,
+        fee_decay_start: BlockNumber,
+        fee_decay_end: BlockNumber,
+        // vesting
+        locked: Mapping<AccountId, (Balance, Timestamp)>,
+        total_locked: Balance,
     }
 
     #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
@@ -52,9 +200,425 @@ mod subsa {
         NotEnoughBalance,
         NotAllAssetsOwnedByManager,
         ZeroAmount,
+        ConfirmationRequired,
+        NoPendingTransfer,
+        TransferExpired,
+        NoHolders,
+        Overflow,
+        ContractPaused,
+        EmergencyExitDisabled,
+        InvalidSnapshot,
+        ReserveBelowMinimum,
+        BalanceLocked,
+        MaxHoldersReached,
+        InsufficientAllowance,
+        InvalidFee,
+        DuplicateRecipient,
+        RegistryMode,
+        TransferNotFinal,
+        InvariantViolated,
+        SupplyLocked,
+        NamesImmutable,
+        EmptyName,
+        ApprovalRequired,
+        AlreadyApproved,
+        NoSuchProposal,
+        BatchTooLarge,
+        ClawbackRenounced,
+        FreezeRenounced,
+        ManagementRenounced,
+        BelowMinimumBalance,
+        NonZeroBalance,
+        OptInTooRecent,
+        ZeroAddress,
+        SupplyCapExceeded,
+        EscrowNotExpired,
+        CooldownActive,
+        TimelockActive,
+        ReserveRenounced,
+        InvalidRole,
+        NoteTooLong,
+        NoSuchDividend,
+        AlreadyClaimed,
+        NativeTransferFailed,
+    }
+
+    /// The creation parameters of an asset, passed to `new`.
+    /// Note: grouped in a struct, as SCALE encodes tuples of at most 18 elements.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AssetParams {
+        /// A stable asset ID, e.g. to keep the same ID across redeployments, `None` to use
+        /// the address of the contract.
+        pub asset_id: Option<AssetId>,
+        pub asset_name: String,
+        pub unit_name: String,
+        pub total: Balance,
+        pub decimals: u32,
+        pub default_frozen: bool,
+        pub url: String,
+        pub metadata_hash: [u8; 4],
+        pub manager: Option<AccountId>,
+        pub reserve: Option<AccountId>,
+        pub freeze: Option<AccountId>,
+        pub clawback: Option<AccountId>,
+        pub compact_events: bool,
+        pub max_holders: Option<u32>,
+        pub registry_mode: bool,
+        pub allow_escrow: bool,
+        pub names_mutable: bool,
+        pub co_managers: Vec<AccountId>,
+        pub approval_threshold: u32,
+        /// The smallest non-zero balance an account can be left with, 0 to allow any.
+        pub min_balance: Balance,
+        /// Whether accounts can ever be frozen, independently of `default_frozen`.
+        pub freezable: bool,
+        /// The most tokens that can ever be minted, the initial supply included, `None` for
+        /// no cap.
+        pub max_supply: Option<Balance>,
+    }
+
+    /// The static profile of an asset, returned by `asset_info`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AssetInfo {
+        pub asset_id: AssetId,
+        pub asset_name: String,
+        pub unit_name: String,
+        pub total: Balance,
+        pub decimals: u32,
+        pub default_frozen: bool,
+        pub freezable: bool,
+        pub min_balance: Balance,
+        pub url: String,
+        pub metadata_hash: [u8; 4],
+        pub creator_id: AccountId,
+        pub manager_id: AccountId,
+        pub reserve_id: AccountId,
+        pub freeze_id: AccountId,
+        pub clawback_id: AccountId,
+    }
+
+    /// The configuration an asset page is rendered from, returned by `get_asset_config`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AssetConfig {
+        pub asset_name: String,
+        pub unit_name: String,
+        pub total: Balance,
+        pub decimals: u32,
+        pub default_frozen: bool,
+        pub url: String,
+        pub metadata_hash: [u8; 4],
+        pub manager_id: AccountId,
+        pub reserve_id: AccountId,
+        pub freeze_id: AccountId,
+        pub clawback_id: AccountId,
+        pub creator_id: AccountId,
+    }
+
+    /// The mutable operational configuration of an asset, returned by `config`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ContractConfig {
+        pub paused: bool,
+        pub transfer_fee_bps: u16,
+        pub min_reserve: Balance,
+        pub max_holders: Option<u32>,
+        pub strict_clawback: bool,
+        pub supply_locked: bool,
+        pub emergency_exit_enabled: bool,
+        pub large_transfer_threshold: Option<Balance>,
+        pub finality_delay: BlockNumber,
+        pub transfer_cooldown: Timestamp,
+    }
+
+    /// The state of a single account, returned by `account_state`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AccountState {
+        pub balance: Balance,
+        pub opted_in: bool,
+        pub frozen: bool,
+        pub locked: Balance,
+        pub pending: Balance,
+    }
+
+    /// Who authorized a token movement, deciding which holder safeguards apply to it.
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    enum Authorization {
+        /// Sent by the holder or a spender, so large amounts need a confirmation.
+        Holder,
+        /// A committed large transfer being confirmed by its holder.
+        Confirmed,
+        /// Moved by an authority address, e.g. a release from the reserve.
+        Authority,
+    }
+
+    /// A privileged action recorded in the audit log.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AuditAction {
+        Freeze,
+        Unfreeze,
+        Modify,
+        Revoke,
+        Pause,
+        Unpause,
+        Mint,
+        ForceTransfer,
+        LockSupply,
+        Burn,
+    }
+
+    /// An action waiting for the approval of the manager and co-managers.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PendingAction {
+        Modify {
+            manager: Option<AccountId>,
+            reserve: Option<AccountId>,
+            freeze: Option<AccountId>,
+            clawback: Option<AccountId>,
+        },
+        Destroy,
+        Revoke {
+            receiver: AccountId,
+            target: AccountId,
+            amount: Balance,
+        },
+        FreezeReserve {
+            freeze: bool,
+        },
+        SetReconfigDelay {
+            delay: BlockNumber,
+        },
+        ClearRole {
+            role: RoleKind,
+        },
+        DelegateTo {
+            controller: Option<AccountId>,
+        },
+        GrantRole {
+            role: RoleKind,
+            account: AccountId,
+        },
+        ForceTransfer {
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        },
+    }
+
+    /// A privileged action, as used by `required_role`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AdminAction {
+        Modify,
+        ProposeManager,
+        SetReconfigDelay,
+        CancelPending,
+        ClearRole,
+        GrantRole,
+        RevokeRole,
+        Rename,
+        SetMetadata,
+        Destroy,
+        Pause,
+        Unpause,
+        LockSupply,
+        ForceOptIn,
+        PruneOptIn,
+        TakeSnapshot,
+        DepositDividend,
+        SetEmergencyExit,
+        SetLargeTransferThreshold,
+        SetFinalityDelay,
+        SetStrictClawback,
+        SetMinReserve,
+        SetTransferFee,
+        SetTransferHook,
+        SetTransferCooldown,
+        SetEscrowDeadline,
+        ReclaimPending,
+        Mint,
+        BurnFrom,
+        Distribute,
+        Release,
+        LockBalance,
+        Freeze,
+        SetFreezeExempt,
+        SetFreezeDefaultOverride,
+        Revoke,
+        ForceTransfer,
+    }
+
+    /// A role holding one of the asset's authority addresses, or a custom role.
+    /// Note: custom roles aren't checked by the asset itself, integrators can check them
+    /// with `has_role`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum RoleKind {
+        Manager,
+        Reserve,
+        Freeze,
+        Clawback,
+        Custom(u32),
+    }
+
+    /// An entry of the audit log.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AuditEntry {
+        /// The account that performed the action.
+        pub actor: AccountId,
+        /// The action that was performed.
+        pub action: AuditAction,
+        /// The account the action was performed on.
+        pub target: AccountId,
+        /// The block number at which the action was performed.
+        pub block: BlockNumber,
+    }
+
+    /// The messages other contracts need to hold and move an asset.
+    /// Note: integrating contracts can depend on this trait and call `Subsa` through it,
+    /// see "Integrating Contracts" in the readme.
+    #[ink_lang::trait_definition]
+    pub trait AsaToken {
+        /// Opts the caller in to receive the asset.
+        #[ink(message)]
+        fn opt_in(&mut self) -> Result<(), Error>;
+
+        /// Transfers `amount` of tokens from the caller to `receiver`.
+        #[ink(message)]
+        fn transfer(&mut self, receiver: AccountId, amount: Balance) -> Result<(), Error>;
+
+        /// Returns the balance of `account`.
+        #[ink(message)]
+        fn balance_of(&self, account: AccountId) -> Result<Balance, Error>;
+
+        /// Returns whether `account` has opted in to the asset.
+        #[ink(message)]
+        fn is_opted_in(&self, account: AccountId) -> bool;
+    }
+
+    /// The PSP22 fungible token standard, for wallets, DEXes and tooling that speak PSP22.
+    /// Note: kept in its own module so its messages don't shadow the `AsaToken` ones.
+    pub mod psp22 {
+        use super::{AccountId, Balance, Error};
+        use ink_prelude::{string::String, vec::Vec};
+        use scale::{Decode, Encode};
+
+        /// The errors defined by PSP22.
+        #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+        #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+        pub enum PSP22Error {
+            Custom(String),
+            InsufficientBalance,
+            InsufficientAllowance,
+            ZeroRecipientAddress,
+            ZeroSenderAddress,
+            SafeTransferCheckFailed(String),
+        }
+
+        impl From<Error> for PSP22Error {
+            fn from(error: Error) -> Self {
+                match error {
+                    Error::NotEnoughBalance => PSP22Error::InsufficientBalance,
+                    Error::InsufficientAllowance => PSP22Error::InsufficientAllowance,
+                    Error::ZeroAddress => PSP22Error::ZeroRecipientAddress,
+                    error => PSP22Error::Custom(ink_prelude::format!("{:?}", error)),
+                }
+            }
+        }
+
+        /// The PSP22 messages, selected by `blake2("PSP22::<message>")[0..4]`.
+        #[ink_lang::trait_definition]
+        pub trait PSP22 {
+            /// Returns the issued supply.
+            #[ink(message)]
+            fn total_supply(&self) -> Balance;
+
+            /// Returns the balance of `owner`, 0 if it hasn't opted in.
+            #[ink(message)]
+            fn balance_of(&self, owner: AccountId) -> Balance;
+
+            /// Returns the amount `spender` is allowed to withdraw from `owner`.
+            #[ink(message)]
+            fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+            /// Transfers `value` of tokens from the caller to `to`.
+            #[ink(message)]
+            fn transfer(
+                &mut self,
+                to: AccountId,
+                value: Balance,
+                data: Vec<u8>,
+            ) -> Result<(), PSP22Error>;
+
+            /// Transfers `value` of tokens from `from` to `to` on behalf of `from`.
+            #[ink(message)]
+            fn transfer_from(
+                &mut self,
+                from: AccountId,
+                to: AccountId,
+                value: Balance,
+                data: Vec<u8>,
+            ) -> Result<(), PSP22Error>;
+
+            /// Allows `spender` to withdraw up to `value` of the caller's tokens.
+            #[ink(message)]
+            fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+            /// Raises the allowance of `spender` over the caller's tokens by `delta_value`.
+            #[ink(message)]
+            fn increase_allowance(
+                &mut self,
+                spender: AccountId,
+                delta_value: Balance,
+            ) -> Result<(), PSP22Error>;
+
+            /// Lowers the allowance of `spender` over the caller's tokens by `delta_value`.
+            #[ink(message)]
+            fn decrease_allowance(
+                &mut self,
+                spender: AccountId,
+                delta_value: Balance,
+            ) -> Result<(), PSP22Error>;
+        }
+
+        /// The PSP22 metadata extension, selected by `blake2("PSP22Metadata::<message>")[0..4]`.
+        #[ink_lang::trait_definition]
+        pub trait PSP22Metadata {
+            /// Returns the token name.
+            #[ink(message)]
+            fn token_name(&self) -> Option<String>;
+
+            /// Returns the token symbol.
+            #[ink(message)]
+            fn token_symbol(&self) -> Option<String>;
+
+            /// Returns the number of decimals used to display the token.
+            #[ink(message)]
+            fn token_decimals(&self) -> u8;
+        }
+    }
+
+    /// The callback a contract implements to be notified of transfers by `set_transfer_hook`.
+    /// Note: the hook is called after the balances are updated and returns whether it
+    /// accepts the transfer.
+    /// Note: `Subsa` only calls this trait, hook contracts implement it.
+    #[allow(dead_code)]
+    #[ink_lang::trait_definition]
+    pub trait TransferHook {
+        /// Called after `amount` of tokens moved from `from` to `to`.
+        #[ink(message)]
+        fn on_transfer(&mut self, from: AccountId, to: AccountId, amount: Balance) -> bool;
     }
 
     /// Event emitted when a token transfer occurs.
+    /// Note: mints are sent from the zero address and burns to the zero address.
+    /// Note: `note` is the reference attached with `transfer_with_note`, empty otherwise.
     #[ink(event)]
     pub struct Transfer {
         #[ink(topic)]
@@ -63,8 +627,66 @@ mod subsa {
         receiver: AccountId,
         #[ink(topic)]
         asset_id: AssetId,
+        amount: Balance,
+        note: Vec<u8>,
+    }
+
+    /// Event emitted when an owner approves a spender.
+    #[ink(event)]
+    pub struct Approval {
         #[ink(topic)]
-        amount: Option<Balance>,
+        asset_id: AssetId,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the manager deposits a dividend in native currency.
+    /// Note: holders claim their share pro rata to their balance at `snapshot_id`.
+    #[ink(event)]
+    pub struct DividendDeposited {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        dividend_id: u32,
+        snapshot_id: u32,
+        amount: Balance,
+    }
+
+    /// Event emitted when a holder claims its share of a dividend.
+    #[ink(event)]
+    pub struct DividendClaimed {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        dividend_id: u32,
+        amount: Balance,
+    }
+
+    /// Event emitted when a balance snapshot is taken.
+    /// Note: only the manager can take a snapshot.
+    #[ink(event)]
+    pub struct Snapshot {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        snapshot_id: u32,
+        block_number: BlockNumber,
+    }
+
+    /// Event emitted in place of any other event when the asset uses the compact event schema.
+    /// Note: `data` is the SCALE encoded `Event` that would have been emitted otherwise.
+    #[ink(event)]
+    pub struct Compact {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        account: AccountId,
+        data: Vec<u8>,
     }
 
     /// Event emitted when an asset is created.
@@ -72,16 +694,16 @@ mod subsa {
     pub struct Creation {
         #[ink(topic)]
         asset_id: AssetId,
-        #[ink(topic)]
         asset_name: String,
         #[ink(topic)]
         creator: AccountId,
-        #[ink(topic)]
         total: Balance,
     }
 
     /// Event emitted when an asset is frozen.
     /// Note: only the freeze account can freeze an account.
+    /// Note: `previous` is the frozen status of the account before this change.
+    /// Note: `reason` is the compliance reason code of the freeze, 0 when unfrozen.
     #[ink(event)]
     pub struct Freeze {
         #[ink(topic)]
@@ -90,8 +712,9 @@ mod subsa {
         account: AccountId,
         #[ink(topic)]
         freeze_id: AccountId,
-        #[ink(topic)]
         freeze: bool,
+        previous: bool,
+        reason: u8,
     }
 
     /// Event emitted when an asset is reconfigured.
@@ -101,21 +724,77 @@ mod subsa {
     pub struct Modify {
         #[ink(topic)]
         manager_id: AccountId,
-        #[ink(topic)]
         reserve_id: AccountId,
-        #[ink(topic)]
         freeze_id: AccountId,
-        #[ink(topic)]
         clawback_id: AccountId,
     }
 
+    /// Event emitted when the manager proposes a new manager.
+    /// Note: the current manager stays active until the proposed one accepts.
+    #[ink(event)]
+    pub struct ManagerProposed {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        manager_id: AccountId,
+        #[ink(topic)]
+        proposed: AccountId,
+    }
+
+    /// Event emitted when a proposed manager accepts the handover.
+    #[ink(event)]
+    pub struct ManagerChanged {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        manager_id: AccountId,
+    }
+
+    /// Event emitted when an authority address is cleared for good.
+    /// Note: `previous` is the address that held the role until then.
+    #[ink(event)]
+    pub struct RoleCleared {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        role: RoleKind,
+        #[ink(topic)]
+        previous: AccountId,
+    }
+
+    /// Event emitted when the manager grants a role to an account.
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        role: RoleKind,
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when a role grant is revoked by the manager or renounced by its holder.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        role: RoleKind,
+        #[ink(topic)]
+        account: AccountId,
+    }
+
     /// Event emitted when an account opts in to receive an asset.
+    /// Note: `frozen` is the frozen status of the account right after opting in.
     #[ink(event)]
     pub struct OptIn {
         #[ink(topic)]
         asset_id: AssetId,
         #[ink(topic)]
         account: AccountId,
+        frozen: bool,
     }
 
     /// Event emitted when an account opts out of receiving an asset.
@@ -129,7 +808,7 @@ mod subsa {
     }
 
     /// Event emitted when an asset is revoked.
-    /// Note: only the manager address can revoke an asset.
+    /// Note: only the clawback address can revoke an asset.
     #[ink(event)]
     pub struct Revoke {
         #[ink(topic)]
@@ -138,7 +817,6 @@ mod subsa {
         from: AccountId,
         #[ink(topic)]
         clawback_id: AccountId,
-        #[ink(topic)]
         amount: Option<Balance>,
     }
 
@@ -153,89 +831,403 @@ mod subsa {
         destroyer: AccountId,
     }
 
-    impl Subsa {
-        #[ink(constructor)]
-        pub fn new(
-            asset_name: String,
-            unit_name: String,
-            total: Balance,
-            decimals: u32,
-            default_frozen: bool,
-            url: String,
-            metadata_hash: [u8; 4],
-            manager: Option<AccountId>,
-            reserve: Option<AccountId>,
-            freeze: Option<AccountId>,
-            clawback: Option<AccountId>,
-        ) -> Self {
-            // emit creation event
-            Self::env().emit_event(Creation {
-                asset_id: Self::env().account_id(),
-                asset_name: asset_name.clone(),
-                creator: Self::env().caller(),
-                total,
-            });
+    /// Event emitted when the asset's URL and metadata hash are updated together.
+    /// Note: only the manager can update the metadata.
+    #[ink(event)]
+    pub struct MetadataUpdated {
+        #[ink(topic)]
+        asset_id: AssetId,
+        old_url: String,
+        new_url: String,
+        old_metadata_hash: [u8; 4],
+        new_metadata_hash: [u8; 4],
+    }
 
-            // handle balance of creator and reserve address
-            // if reserve address is not provided, creator is the reserve address
-            let reserve_id = reserve.unwrap_or_else(|| Self::env().caller());
+    /// Event emitted when the asset is renamed.
+    /// Note: only the manager can rename an asset, and only if its names are mutable.
+    #[ink(event)]
+    pub struct Rename {
+        #[ink(topic)]
+        manager_id: AccountId,
+        asset_name: String,
+        unit_name: String,
+    }
 
-            let mut accounts_opted_in = Mapping::default();
-            accounts_opted_in.insert(reserve_id, &true);
+    /// Event emitted when the supply of the asset is locked for good.
+    /// Note: only the manager can lock the supply.
+    #[ink(event)]
+    pub struct SupplyLocked {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        manager_id: AccountId,
+    }
 
-            let mut balances = Mapping::default();
-            balances.insert(reserve_id, &total);
+    /// Event emitted when the asset is paused.
+    /// Note: only the manager can pause an asset.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        manager_id: AccountId,
+    }
 
-            // initialize asset params
-            Self {
-                creator: Self::env().caller(),
-                asset_name,
-                unit_name,
-                total,
-                decimals,
-                default_frozen,
-                url,
-                metadata_hash,
-                manager_id: manager.unwrap_or_else(|| AccountId::from([0x0; 32])),
-                reserve_id,
-                freeze_id: freeze.unwrap_or_else(|| AccountId::from([0x0; 32])),
-                clawback_id: clawback.unwrap_or_else(|| AccountId::from([0x0; 32])),
-                balances,
-                accounts_opted_in,
-                frozen_holders: Mapping::default(),
-            }
-        }
+    /// Event emitted when the asset is unpaused.
+    /// Note: only the manager can unpause an asset.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        manager_id: AccountId,
+    }
 
-        /// Returns the asset name.
+    /// Event emitted when a holder moves their full balance out through the emergency exit.
+    #[ink(event)]
+    pub struct EmergencyExit {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the reserve mints new tokens.
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when tokens are burned from a holder.
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a holder returns tokens to the reserve through redeem.
+    #[ink(event)]
+    pub struct Redeem {
+        #[ink(topic)]
+        asset_id: AssetId,
+        #[ink(topic)]
+        holder: AccountId,
+        amount: Balance,
+    }
+
+    impl AsaToken for Subsa {
+        // OptIn to receive an asset
         #[ink(message)]
-        pub fn asset_name(&self) -> String {
-            self.asset_name.clone()
+        fn opt_in(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.opt_in_account(caller)
         }
 
-        /// Returns the asset unit name.
+        /// Transfer `amount` of tokens from `sender` to `receiver`.
+        /// Note: amounts above the large transfer threshold must go through
+        /// `commit_large_transfer` and `confirm_large_transfer` instead.
         #[ink(message)]
-        pub fn unit_name(&self) -> String {
-            self.unit_name.clone()
+        fn transfer(&mut self, receiver: AccountId, amount: Balance) -> Result<(), Error> {
+            let sender = self.env().caller();
+
+            self.transfer_from_to(sender, receiver, amount, Authorization::Holder)?;
+            self.notify_transfer_hook(sender, receiver, amount);
+
+            Ok(())
         }
 
-        /// Returns the total supply of the asset.
+        /// Returns the balance of `account`.
+        /// Note: if the account has not opted in to this asset, NotOptedIn is returned.
         #[ink(message)]
-        pub fn total(&self) -> Balance {
-            self.total
+        fn balance_of(&self, account: AccountId) -> Result<Balance, Error> {
+            let opted_in = self.accounts_opted_in.get(account).unwrap_or(false);
+            if !opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            Ok(self.balances.get(account).unwrap_or(0))
         }
 
-        /// Returns the number of decimals used to display the asset.
+        /// Returns whether `account` has opted in to this asset.
         #[ink(message)]
-        pub fn decimals(&self) -> u32 {
+        fn is_opted_in(&self, account: AccountId) -> bool {
+            self.accounts_opted_in.get(account).unwrap_or(false)
+        }
+    }
+
+    impl psp22::PSP22 for Subsa {
+        /// Returns the issued supply, see `total_supply`.
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.issued
+        }
+
+        /// Returns the balance of `owner`.
+        /// Note: unlike `balance_of`, an account that hasn't opted in has a balance of 0.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        /// Returns the amount `spender` is allowed to withdraw from `owner`.
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// Transfers `value` of tokens from the caller to `to`, like `transfer`.
+        /// Note: `data` is ignored.
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), psp22::PSP22Error> {
+            <Subsa as AsaToken>::transfer(self, to, value)?;
+
+            Ok(())
+        }
+
+        /// Transfers `value` of tokens from `from` to `to`, like `transfer_from`.
+        /// Note: `data` is ignored.
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), psp22::PSP22Error> {
+            Subsa::transfer_from(self, from, to, value)?;
+
+            Ok(())
+        }
+
+        /// Allows `spender` to withdraw up to `value` of the caller's tokens, like `approve`.
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), psp22::PSP22Error> {
+            Subsa::approve(self, spender, value)?;
+
+            Ok(())
+        }
+
+        /// Raises the allowance of `spender` by `delta_value`, like `increase_allowance`.
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), psp22::PSP22Error> {
+            Subsa::increase_allowance(self, spender, delta_value)?;
+
+            Ok(())
+        }
+
+        /// Lowers the allowance of `spender` by `delta_value`, like `decrease_allowance`.
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), psp22::PSP22Error> {
+            Subsa::decrease_allowance(self, spender, delta_value)?;
+
+            Ok(())
+        }
+    }
+
+    impl psp22::PSP22Metadata for Subsa {
+        /// Returns the asset name.
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            Some(self.asset_name.clone())
+        }
+
+        /// Returns the asset unit name.
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            Some(self.unit_name.clone())
+        }
+
+        /// Returns the number of decimals of the asset.
+        /// Note: PSP22 decimals are a `u8`, larger values are capped at `u8::MAX`.
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            u8::try_from(self.decimals).unwrap_or(u8::MAX)
+        }
+    }
+
+    impl Subsa {
+        #[ink(constructor)]
+        pub fn new(params: AssetParams) -> Self {
+            let AssetParams {
+                asset_id,
+                asset_name,
+                unit_name,
+                total,
+                decimals,
+                default_frozen,
+                url,
+                metadata_hash,
+                manager,
+                reserve,
+                freeze,
+                clawback,
+                compact_events,
+                max_holders,
+                registry_mode,
+                allow_escrow,
+                names_mutable,
+                co_managers,
+                approval_threshold,
+                min_balance,
+                freezable,
+                max_supply,
+            } = params;
+
+            // names can't be empty
+            assert!(
+                !asset_name.is_empty() && !unit_name.is_empty(),
+                "asset and unit names must not be empty"
+            );
+
+            // the initial supply counts towards the cap
+            assert!(
+                max_supply.is_none_or(|max_supply| total <= max_supply),
+                "initial supply exceeds the max supply"
+            );
+
+            // the manager and co-managers must be able to reach the threshold
+            assert!(
+                approval_threshold as usize <= co_managers.len() + 1,
+                "approval threshold exceeds the number of managers"
+            );
+
+            ink_lang::utils::initialize_contract(|contract: &mut Self| {
+                contract.compact_events = compact_events;
+                contract.max_holders = max_holders;
+                contract.registry_mode = registry_mode;
+                contract.allow_escrow = allow_escrow;
+                contract.names_mutable = names_mutable;
+                contract.co_managers = co_managers;
+                contract.approval_threshold = approval_threshold;
+                contract.min_balance = min_balance;
+                contract.asset_id = asset_id;
+
+                // emit creation event
+                contract.emit(
+                    Self::env().caller(),
+                    Creation {
+                        asset_id: contract.asset_id(),
+                        asset_name: asset_name.clone(),
+                        creator: Self::env().caller(),
+                        total,
+                    },
+                );
+
+                // handle balance of creator and reserve address
+                // if reserve address is not provided, creator is the reserve address
+                let reserve_id = reserve.unwrap_or_else(|| Self::env().caller());
+
+                contract.accounts_opted_in.insert(reserve_id, &true);
+                contract.index_holder(reserve_id);
+                contract
+                    .opt_in_ts
+                    .insert(reserve_id, &Self::env().block_timestamp());
+                contract.balances.insert(reserve_id, &total);
+                contract.holder_count = u32::from(total > 0);
+                contract.top_holder = reserve_id;
+                // the reserve is never frozen by default
+                contract.frozen_holders.insert(reserve_id, &false);
+
+                // initialize asset params
+                contract.creator = Self::env().caller();
+                contract.asset_name = asset_name;
+                contract.unit_name = unit_name;
+                contract.total = total;
+                contract.issued = total;
+                contract.sum_balances = total;
+                contract.minted = total;
+                contract.max_supply = max_supply;
+                contract.decimals = decimals;
+                contract.default_frozen = default_frozen;
+                contract.freezable = freezable;
+                contract.url = url;
+                contract.metadata_hash = metadata_hash;
+                contract.manager_id = manager.unwrap_or_else(|| AccountId::from([0x0; 32]));
+                contract.reserve_id = reserve_id;
+                contract.freeze_id = freeze.unwrap_or_else(|| AccountId::from([0x0; 32]));
+                contract.clawback_id = clawback.unwrap_or_else(|| AccountId::from([0x0; 32]));
+            })
+        }
+
+        /// Returns the asset name.
+        #[ink(message)]
+        pub fn asset_name(&self) -> String {
+            self.asset_name.clone()
+        }
+
+        /// Returns the asset unit name.
+        #[ink(message)]
+        pub fn unit_name(&self) -> String {
+            self.unit_name.clone()
+        }
+
+        /// Returns the initial supply of the asset, issued to the reserve at creation.
+        /// Note: minting and burning change `total_supply`, the initial supply never changes.
+        #[ink(message)]
+        pub fn total(&self) -> Balance {
+            self.total
+        }
+
+        /// Returns the most tokens that can ever be minted, the initial supply included.
+        /// Note: `None` means minting is uncapped.
+        #[ink(message)]
+        pub fn max_supply(&self) -> Option<Balance> {
+            self.max_supply
+        }
+
+        /// Returns the issued supply of the asset, the initial supply plus minted minus
+        /// burned tokens.
+        /// Note: unlike `circulating_supply`, the issued supply includes the reserve balance.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.issued
+        }
+
+        /// Returns the number of decimals used to display the asset.
+        #[ink(message)]
+        pub fn decimals(&self) -> u32 {
             self.decimals
         }
 
         /// Returns whether the asset is frozen by default.
+        /// Note: new holders of a default frozen asset start frozen until the freeze address
+        /// unfreezes them.
         #[ink(message)]
         pub fn default_frozen(&self) -> bool {
             self.default_frozen
         }
 
+        /// Returns whether accounts can be frozen.
+        /// Note: freezability is chosen at creation and never changes.
+        #[ink(message)]
+        pub fn freezable(&self) -> bool {
+            self.freezable
+        }
+
         /// Returns the URL of the asset.
         #[ink(message)]
         pub fn url(&self) -> String {
@@ -249,10 +1241,10 @@ mod subsa {
         }
 
         /// Returns the asset ID.
-        /// Note: the asset ID is the address of the contract.
+        /// Note: unless set at creation, the asset ID is the address of the contract.
         #[ink(message)]
-        pub fn asset_id(&self) -> AccountId {
-            self.env().account_id()
+        pub fn asset_id(&self) -> AssetId {
+            self.asset_id.unwrap_or_else(|| self.env().account_id())
         }
 
         // Returns the creator address.
@@ -285,490 +1277,9041 @@ mod subsa {
             self.clawback_id
         }
 
-        /// Returns the balance of `account`.
-        /// Note: if the account has not opted in to this asset, NotOptedIn is returned.
+        /// Returns the asset parameters and authority addresses in a single call.
         #[ink(message)]
-        pub fn balance_of(&self, account: AccountId) -> Result<Balance, Error> {
-            let opted_in = self.accounts_opted_in.get(&account).unwrap_or(false);
-            if !opted_in {
-                return Err(Error::NotOptedIn);
+        pub fn asset_info(&self) -> AssetInfo {
+            AssetInfo {
+                asset_id: self.asset_id(),
+                asset_name: self.asset_name.clone(),
+                unit_name: self.unit_name.clone(),
+                total: self.total,
+                decimals: self.decimals,
+                default_frozen: self.default_frozen,
+                freezable: self.freezable,
+                min_balance: self.min_balance,
+                url: self.url.clone(),
+                metadata_hash: self.metadata_hash,
+                creator_id: self.creator,
+                manager_id: self.manager_id,
+                reserve_id: self.reserve_id,
+                freeze_id: self.freeze_id,
+                clawback_id: self.clawback_id,
             }
-
-            Ok(self.balances.get(&account).unwrap_or(0))
         }
 
-        /// Returns whether `account` is frozen.
+        /// Returns the configuration of the asset in a single call, e.g. to render its page.
         #[ink(message)]
-        pub fn is_frozen(&self, account: AccountId) -> Result<bool, Error> {
-            Ok(self.frozen_holders.get(&account).unwrap_or(false))
+        pub fn get_asset_config(&self) -> AssetConfig {
+            AssetConfig {
+                asset_name: self.asset_name.clone(),
+                unit_name: self.unit_name.clone(),
+                total: self.total,
+                decimals: self.decimals,
+                default_frozen: self.default_frozen,
+                url: self.url.clone(),
+                metadata_hash: self.metadata_hash,
+                manager_id: self.manager_id,
+                reserve_id: self.reserve_id,
+                freeze_id: self.freeze_id,
+                clawback_id: self.clawback_id,
+                creator_id: self.creator,
+            }
         }
 
-        /// Returns whether `account` has opted in to this asset.
+        /// Returns the operational flags and limits in a single call.
+        /// Note: `transfer_fee_bps` is the fee at the current block.
         #[ink(message)]
-        pub fn is_opted_in(&self, account: AccountId) -> Result<bool, Error> {
-            Ok(self.accounts_opted_in.get(&account).unwrap_or(false))
+        pub fn config(&self) -> ContractConfig {
+            ContractConfig {
+                paused: self.paused,
+                transfer_fee_bps: self.current_fee_bps(),
+                min_reserve: self.min_reserve,
+                max_holders: self.max_holders,
+                strict_clawback: self.strict_clawback,
+                supply_locked: self.supply_locked,
+                emergency_exit_enabled: self.emergency_exit_enabled,
+                large_transfer_threshold: self.large_transfer_threshold,
+                finality_delay: self.finality_delay,
+                transfer_cooldown: self.transfer_cooldown,
+            }
         }
 
-        /// Returns wheter `creator's balance is equal to total supply.
-        /// Note: an asset can only be destroyed if the creator's balance is equal to the total supply.
+        /// Returns the balance, opt-in and frozen status, locked and pending balances of
+        /// `account` in a single call.
+        /// Note: accounts that haven't opted in have a balance of 0.
         #[ink(message)]
-        pub fn is_destroyable(&self) -> bool {
-            self.balances.get(&self.creator).unwrap_or(0) == self.total
+        pub fn account_state(&self, account: AccountId) -> AccountState {
+            AccountState {
+                balance: self.balance_of(account).unwrap_or(0),
+                opted_in: self.is_opted_in(account),
+                frozen: self.is_frozen(account),
+                locked: self.locked_balance(account),
+                pending: self.pending_balance(account),
+            }
         }
 
-        /// Transfer `amount` of tokens from `sender` to `receiver`.
+        /// Returns the balance of each of `accounts`, in order.
+        /// Note: accounts that never held the asset have a balance of 0.
+        /// Note: at most `MAX_BATCH` accounts can be read at once, BatchTooLarge is returned otherwise.
         #[ink(message)]
-        pub fn transfer(&mut self, receiver: AccountId, amount: Balance) -> Result<(), Error> {
-            let sender = self.env().caller();
-
-            // check if sender has enough balance
-            let sender_balance = self.balances.get(&sender).unwrap_or(0);
-            if sender_balance < amount {
-                return Err(Error::NotEnoughBalance);
-            }
-
-            // check if receiver has opted in
-            let receiver_opted_in = self.accounts_opted_in.get(&receiver).unwrap_or(false);
-            if !receiver_opted_in {
-                return Err(Error::NotOptedIn);
+        pub fn balances_of(&self, accounts: Vec<AccountId>) -> Result<Vec<Balance>, Error> {
+            // check if batch is small enough
+            if accounts.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
             }
 
-            // update sender and receiver balances
-            self.balances.insert(&sender, &(sender_balance - amount));
-            self.balances.insert(
-                &receiver,
-                &(self.balances.get(&receiver).unwrap_or(0) + amount),
-            );
-
-            // emit transfer event
-            self.env().emit_event(Transfer {
-                sender,
-                receiver,
-                asset_id: self.asset_id(),
-                amount: Some(amount),
-            });
-
-            Ok(())
+            Ok(accounts
+                .into_iter()
+                .map(|account| self.balances.get(account).unwrap_or(0))
+                .collect())
         }
 
-        // OptIn to receive an asset
+        /// Returns whether each of `accounts` is frozen, in order.
+        /// Note: at most `MAX_BATCH` accounts can be read at once, BatchTooLarge is returned otherwise.
         #[ink(message)]
-        pub fn opt_in(&mut self) -> Result<(), Error> {
-            let caller = self.env().caller();
-
-            // check if caller has already opted in
-            let caller_opted_in = self.accounts_opted_in.get(&caller).unwrap_or(false);
-            if caller_opted_in {
-                return Err(Error::AlreadyOptedIn);
+        pub fn frozen_of(&self, accounts: Vec<AccountId>) -> Result<Vec<bool>, Error> {
+            // check if batch is small enough
+            if accounts.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
             }
 
-            // update caller's opt in status
-            self.accounts_opted_in.insert(&caller, &true);
-
-            // emit opt in event
-            self.env().emit_event(OptIn {
-                asset_id: self.asset_id(),
-                account: caller,
-            });
-
-            Ok(())
+            Ok(accounts
+                .into_iter()
+                .map(|account| self.is_frozen(account))
+                .collect())
         }
 
-        // OptOut of receiving an asset
+        /// Returns whether `account` is frozen.
+        /// Note: accounts without an explicit frozen status fall back to their default
+        /// override, then to `default_frozen`.
         #[ink(message)]
-        pub fn opt_out(&mut self) -> Result<(), Error> {
-            let caller = self.env().caller();
-
-            // check if caller has opted in
-            let caller_opted_in = self.accounts_opted_in.get(&caller).unwrap_or(false);
-            if !caller_opted_in {
-                return Err(Error::NotOptedIn);
-            }
-
-            // update caller's opt in status
-            self.accounts_opted_in.insert(&caller, &false);
-
-            // emit opt out event
-            self.env().emit_event(OptOut {
-                asset_id: self.asset_id(),
-                account: caller,
-            });
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            self.frozen_holders
+                .get(account)
+                .unwrap_or_else(|| self.default_frozen_of(account))
+        }
 
-            Ok(())
+        /// Returns the default frozen status set for `account` by the freeze address, if any.
+        #[ink(message)]
+        pub fn freeze_default_override(&self, account: AccountId) -> Option<bool> {
+            self.freeze_default_override.get(account)
         }
 
-        // Freeze an account
+        /// Sets the default frozen status of `account`, taking precedence over `default_frozen`
+        /// while the account has no explicit frozen status. `None` removes the override.
+        /// Note: only the freeze address can set default overrides.
         #[ink(message)]
-        pub fn freeze(&mut self, account: AccountId, freeze: bool) -> Result<(), Error> {
+        pub fn set_freeze_default_override(
+            &mut self,
+            account: AccountId,
+            frozen: Option<bool>,
+        ) -> Result<(), Error> {
             let caller = self.env().caller();
 
             // check if token can be frozen
-            if !self.default_frozen {
+            if !self.freezable {
                 return Err(Error::NotFreezable);
             }
 
+            // check if freezing has been renounced
+            if self.freeze_renounced {
+                return Err(Error::FreezeRenounced);
+            }
+
             // check if caller is the freeze address
-            if caller != self.freeze_id {
+            if !self.is_authorized(caller, RoleKind::Freeze) {
                 return Err(Error::NotFreezeId);
             }
 
-            // check if account is already frozen
-            let account_frozen = self.frozen_holders.get(&account).unwrap_or(false);
-            if account_frozen {
-                return Err(Error::AlreadyFrozen);
+            let was_frozen = self.is_frozen(account);
+            match frozen {
+                Some(frozen) => self.freeze_default_override.insert(account, &frozen),
+                None => self.freeze_default_override.remove(account),
             }
 
-            // update account's frozen status
-            self.frozen_holders.insert(&account, &freeze);
-
-            // emit freeze event
-            self.env().emit_event(Freeze {
-                asset_id: self.asset_id(),
-                account,
-                freeze,
-                freeze_id: self.freeze_id,
-            });
+            // keep the frozen balance counter in sync
+            let balance = self.balances.get(account).unwrap_or(0);
+            match (was_frozen, self.is_frozen(account)) {
+                (false, true) => self.total_frozen += balance,
+                (true, false) => self.total_frozen -= balance,
+                _ => {}
+            }
 
             Ok(())
         }
 
-        // Modify/Reconfigure an asset
-        // Note: only the manager can modify an asset
-        // Note: only mutable asset params can be modified
-        // List of mutable asset params:
-        // - managerId, reserveId, freezeId, clawbackId
+        /// Returns the compliance reason code `account` was frozen with.
+        /// Note: accounts that aren't frozen, or were frozen without a reason, return 0.
         #[ink(message)]
-        pub fn modify_asset(
-            &mut self,
-            manager: Option<AccountId>,
-            reserve: Option<AccountId>,
-            freeze: Option<AccountId>,
-            clawback: Option<AccountId>,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
+        pub fn freeze_reason(&self, account: AccountId) -> u8 {
+            self.freeze_reasons.get(account).unwrap_or(0)
+        }
 
-            // check if caller is the manager
-            if caller != self.manager_id {
-                return Err(Error::NotManagerId);
-            }
+        /// Returns the circulating supply of the asset, computed as
+        /// `issued supply - reserve balance - frozen balances - locked balances`,
+        /// where the issued supply is the initial supply plus minted minus burned tokens.
+        /// Note: an expired lock keeps counting as locked until the balance of its account changes.
+        #[ink(message)]
+        pub fn circulating_supply(&self) -> Balance {
+            self.issued
+                .saturating_sub(self.balances.get(self.reserve_id).unwrap_or(0))
+                .saturating_sub(self.total_frozen)
+                .saturating_sub(self.total_locked)
+        }
 
-            // update asset params
-            self.manager_id = manager.unwrap_or_else(|| AccountId::from([0x0; 32]));
-            self.reserve_id = reserve.unwrap_or_else(|| AccountId::from([0x0; 32]));
-            self.freeze_id = freeze.unwrap_or_else(|| AccountId::from([0x0; 32]));
-            self.clawback_id = clawback.unwrap_or_else(|| AccountId::from([0x0; 32]));
+        /// Checks that the sum of all balances, pending claims and assignments equals the
+        /// issued supply.
+        /// Note: any divergence signals a balance mutation that bypassed the supply accounting.
+        #[ink(message)]
+        pub fn check_invariant(&self) -> bool {
+            self.sum_balances == self.issued
+        }
 
-            // emit modify asset event
-            self.env().emit_event(Modify {
-                manager_id: self.manager_id,
-                reserve_id: self.reserve_id,
-                freeze_id: self.freeze_id,
-                clawback_id: self.clawback_id,
-            });
+        /// Checks that the aggregate counters are consistent with each other.
+        /// Note: returns InvariantViolated if the minted supply exceeds the max supply, the issued supply
+        /// exceeds the minted supply, the frozen or locked balances exceed the issued supply,
+        /// there are more holders than allowed or than opted in accounts, or `check_invariant` fails.
+        #[ink(message)]
+        pub fn verify_invariants(&self) -> Result<(), Error> {
+            let reserve_balance = self.balances.get(self.reserve_id).unwrap_or(0);
+            let holders_ok = self
+                .max_holders
+                .is_none_or(|max_holders| self.opted_in_count <= max_holders)
+                && self.holder_count <= self.opted_in_count;
+
+            if self
+                .max_supply
+                .is_some_and(|max_supply| self.minted > max_supply)
+                || self.issued > self.minted
+                || reserve_balance > self.issued
+                || self.total_frozen > self.issued
+                || self.total_locked > self.issued
+                || !holders_ok
+                || !self.check_invariant()
+            {
+                return Err(Error::InvariantViolated);
+            }
 
             Ok(())
         }
 
-        // Revoke an asset
-        // Note: only the clawback address can revoke an asset
-        // Note: must specify amount, revocation target id, and receiver
+        /// Returns the lifetime supply returned to the reserve through redeem.
         #[ink(message)]
-        pub fn revoke_asset(
-            &mut self,
-            receiver: AccountId,
-            recovation_target: AccountId,
-            amount: Balance,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
+        pub fn redeemed_supply(&self) -> Balance {
+            self.redeemed_total
+        }
 
-            // check if caller is the clawback address
-            if caller != self.clawback_id {
-                return Err(Error::NotClawbackId);
-            }
+        /// Returns the lifetime minted supply, including the initial supply.
+        /// Note: burns don't reduce the minted supply.
+        #[ink(message)]
+        pub fn minted(&self) -> Balance {
+            self.minted
+        }
 
-            // check if receiver has opted in
-            let receiver_opted_in = self.accounts_opted_in.get(&receiver).unwrap_or(false);
-            if !receiver_opted_in {
-                return Err(Error::NotOptedIn);
+        /// Returns the version of the contract's message surface.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            CONTRACT_VERSION
+        }
+
+        /// Returns whether the optional feature `feature_id` is enabled on this instance.
+        /// Note: features this contract doesn't implement, such as KYC, and unknown ids return false.
+        /// Note: snapshots are reported once the first snapshot has been taken.
+        #[ink(message)]
+        pub fn supports_feature(&self, feature_id: u32) -> bool {
+            match feature_id {
+                FEATURE_ESCROW => self.allow_escrow,
+                FEATURE_FEES => self.fee_start_bps > 0 || self.fee_floor_bps > 0,
+                FEATURE_SNAPSHOTS => self.snapshot_count > 0,
+                FEATURE_REGISTRY => self.registry_mode,
+                FEATURE_RENAMING => self.names_mutable,
+                FEATURE_MULTI_APPROVAL => self.approval_threshold > 1,
+                FEATURE_KYC => false,
+                _ => false,
             }
+        }
 
-            // check if recovation target account has enough balance
-            let receiver_balance = self.balances.get(&receiver).unwrap_or(0);
-            if receiver_balance < amount {
-                return Err(Error::NotEnoughBalance);
+        /// Returns the role whose address is required to perform `action`.
+        #[ink(message)]
+        pub fn required_role(&self, action: AdminAction) -> RoleKind {
+            match action {
+                AdminAction::Mint
+                | AdminAction::BurnFrom
+                | AdminAction::Distribute
+                | AdminAction::Release
+                | AdminAction::LockBalance => RoleKind::Reserve,
+                AdminAction::Freeze
+                | AdminAction::SetFreezeExempt
+                | AdminAction::SetFreezeDefaultOverride => RoleKind::Freeze,
+                AdminAction::Revoke | AdminAction::ForceTransfer => RoleKind::Clawback,
+                AdminAction::Modify
+                | AdminAction::ProposeManager
+                | AdminAction::SetReconfigDelay
+                | AdminAction::CancelPending
+                | AdminAction::ClearRole
+                | AdminAction::GrantRole
+                | AdminAction::RevokeRole
+                | AdminAction::Rename
+                | AdminAction::SetMetadata
+                | AdminAction::Destroy
+                | AdminAction::Pause
+                | AdminAction::Unpause
+                | AdminAction::LockSupply
+                | AdminAction::ForceOptIn
+                | AdminAction::PruneOptIn
+                | AdminAction::TakeSnapshot
+                | AdminAction::DepositDividend
+                | AdminAction::SetEmergencyExit
+                | AdminAction::SetLargeTransferThreshold
+                | AdminAction::SetFinalityDelay
+                | AdminAction::SetStrictClawback
+                | AdminAction::SetMinReserve
+                | AdminAction::SetTransferFee
+                | AdminAction::SetTransferHook
+                | AdminAction::SetTransferCooldown
+                | AdminAction::SetEscrowDeadline
+                | AdminAction::ReclaimPending => RoleKind::Manager,
             }
+        }
 
-            // update recovation target balance
-            self.balances
-                .insert(&recovation_target, &(receiver_balance - amount));
+        /// Returns the lifetime `(total_in, total_out)` token flows of `account`.
+        /// Note: the initial allocation of the reserve isn't counted as an inflow.
+        #[ink(message)]
+        pub fn net_flow(&self, account: AccountId) -> (Balance, Balance) {
+            self.net_flows.get(account).unwrap_or((0, 0))
+        }
 
-            // update receiver balance
-            self.balances
-                .insert(&receiver, &(receiver_balance + amount));
+        /// Returns the lifetime amount of tokens `account` received through transfers,
+        /// mints, distributions, claims and pulls.
+        /// Note: sending tokens out doesn't reduce the received total.
+        #[ink(message)]
+        pub fn received_of(&self, account: AccountId) -> Balance {
+            self.received_totals.get(account).unwrap_or(0)
+        }
 
-            // emit revoke asset event
-            self.env().emit_event(Revoke {
-                asset_id: self.asset_id(),
-                from: receiver,
-                amount: Some(amount),
-                clawback_id: self.clawback_id,
-            });
+        /// Splits `amount` base units into whole units and the fractional remainder,
+        /// according to the asset's `decimals`.
+        /// Note: returns Overflow if `10^decimals` doesn't fit in a `Balance`.
+        #[ink(message)]
+        pub fn to_display_units(&self, amount: Balance) -> Result<(Balance, Balance), Error> {
+            let unit = (10 as Balance)
+                .checked_pow(self.decimals)
+                .ok_or(Error::Overflow)?;
 
-            Ok(())
+            Ok((amount / unit, amount % unit))
         }
 
-        // Destroy an asset
-        // Note: only the manager can destroy an asset
-        // Note: all asset holdings are transferred to the manager
+        /// Returns the share of the issued supply held by `account`, in basis points.
+        /// Note: returns 0 when the issued supply is 0.
         #[ink(message)]
-        pub fn destroy_asset(&mut self) -> Result<(), Error> {
+        pub fn ownership_bps(&self, account: AccountId) -> u16 {
+            let balance = self.balances.get(account).unwrap_or(0);
+            balance
+                .saturating_mul(10_000)
+                .checked_div(self.issued)
+                .map_or(0, |bps| bps.min(10_000) as u16)
+        }
+
+        /// Returns the number of accounts currently opted in to this asset.
+        #[ink(message)]
+        pub fn opted_in_count(&self) -> u32 {
+            self.opted_in_count
+        }
+
+        /// Returns up to `limit` opted in accounts with their balances, starting at position
+        /// `start` of the holder index, e.g. for snapshot tools and airdrop scripts.
+        /// Note: opting out moves the last indexed account into the freed position, so the
+        /// order isn't stable across opt-outs.
+        /// Note: at most `MAX_BATCH` accounts are returned at once.
+        #[ink(message)]
+        pub fn holders(&self, start: u32, limit: u32) -> Vec<(AccountId, Balance)> {
+            let end = start
+                .saturating_add(limit.min(MAX_BATCH as u32))
+                .min(self.opted_in_count);
+            (start..end)
+                .filter_map(|position| self.holder_ids.get(position))
+                .map(|account| (account, self.balances.get(account).unwrap_or(0)))
+                .collect()
+        }
+
+        /// Returns the number of accounts holding a non-zero balance, the reserve included.
+        /// Note: unlike `opted_in_count`, opted in accounts without tokens aren't counted,
+        /// and neither are pending escrowed balances.
+        #[ink(message)]
+        pub fn holder_count(&self) -> u32 {
+            self.holder_count
+        }
+
+        /// Returns the maximum number of accounts that can be opted in at once.
+        /// Note: None means the number of holders is unbounded.
+        #[ink(message)]
+        pub fn max_holders(&self) -> Option<u32> {
+            self.max_holders
+        }
+
+        /// Returns the amount of `account`'s balance that is still locked.
+        /// Note: a lock no longer applies once its release timestamp is reached.
+        #[ink(message)]
+        pub fn locked_balance(&self, account: AccountId) -> Balance {
+            match self.locked.get(account) {
+                Some((amount, release_ts)) if self.env().block_timestamp() < release_ts => amount,
+                _ => 0,
+            }
+        }
+
+        /// Returns the block number of the last balance change of `account`.
+        /// Note: None is returned if the balance of `account` never changed.
+        #[ink(message)]
+        pub fn last_activity_of(&self, account: AccountId) -> Option<BlockNumber> {
+            self.last_activity.get(account)
+        }
+
+        /// Returns the recorded privileged actions, oldest first.
+        /// Note: only the last `AUDIT_LOG_LIMIT` actions are kept, older entries are dropped.
+        #[ink(message)]
+        pub fn audit_log(&self) -> Vec<AuditEntry> {
+            let first = self.audit_count.saturating_sub(AUDIT_LOG_LIMIT);
+
+            (first..self.audit_count)
+                .filter_map(|index| self.audit_entries.get(index % AUDIT_LOG_LIMIT))
+                .collect()
+        }
+
+        /// Returns the largest holder of the asset.
+        /// Note: the top holder is tracked on balance changes, an account takes over once its
+        /// balance exceeds the current top holder's, and the opted in accounts are searched
+        /// for the largest balance when the top holder's balance drops.
+        #[ink(message)]
+        pub fn top_holder(&self) -> AccountId {
+            self.top_holder
+        }
+
+        /// Returns whether `account` is the largest holder of the asset.
+        #[ink(message)]
+        pub fn is_top_holder(&self, account: AccountId) -> bool {
+            account == self.top_holder
+        }
+
+        /// Returns whether the caller is the largest holder of the asset.
+        #[ink(message)]
+        pub fn am_i_top_holder(&self) -> bool {
+            self.is_top_holder(self.env().caller())
+        }
+
+        /// Returns the amount `spender` is allowed to withdraw from `owner`.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// Returns the approval changes of `owner` for `spender` as (block number, allowance),
+        /// oldest first.
+        /// Note: only the last `ALLOWANCE_HISTORY_LIMIT` changes are kept, older entries are dropped.
+        #[ink(message)]
+        pub fn allowance_history(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+        ) -> Vec<(BlockNumber, Balance)> {
+            let len = self
+                .allowance_history_len
+                .get((owner, spender))
+                .unwrap_or(0);
+            let first = len.saturating_sub(ALLOWANCE_HISTORY_LIMIT);
+
+            (first..len)
+                .filter_map(|index| {
+                    self.allowance_history
+                        .get((owner, spender, index % ALLOWANCE_HISTORY_LIMIT))
+                })
+                .collect()
+        }
+
+        /// Returns whether the manager and the reserve hold every token.
+        /// Note: an asset can only be destroyed once all holdings have been returned to them.
+        #[ink(message)]
+        pub fn is_destroyable(&self) -> bool {
+            let mut held = self.balances.get(self.manager_id).unwrap_or(0);
+            if self.reserve_id != self.manager_id {
+                held += self.balances.get(self.reserve_id).unwrap_or(0);
+            }
+
+            held == self.sum_balances
+        }
+
+        /// Returns the large transfer threshold and the confirmation window in blocks.
+        /// Note: `None` means large transfers don't require a confirmation.
+        #[ink(message)]
+        pub fn large_transfer_threshold(&self) -> (Option<Balance>, BlockNumber) {
+            (self.large_transfer_threshold, self.large_transfer_window)
+        }
+
+        /// Sets the amount above which transfers require a two-step confirmation,
+        /// and the number of blocks a committed transfer stays confirmable.
+        /// Note: only the manager can set the large transfer threshold.
+        #[ink(message)]
+        pub fn set_large_transfer_threshold(
+            &mut self,
+            threshold: Option<Balance>,
+            window: BlockNumber,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.large_transfer_threshold = threshold;
+            self.large_transfer_window = window;
+
+            Ok(())
+        }
+
+        /// Returns the number of blocks a committed large transfer must wait before
+        /// it can be confirmed.
+        #[ink(message)]
+        pub fn finality_delay(&self) -> BlockNumber {
+            self.finality_delay
+        }
+
+        /// Sets the number of blocks a committed large transfer must wait before it can be
+        /// confirmed, so relying contracts only see it executed once its commit is final.
+        /// Note: only the manager can set the finality delay.
+        /// Note: the confirmation window starts once the delay has passed.
+        #[ink(message)]
+        pub fn set_finality_delay(&mut self, delay: BlockNumber) -> Result<(), Error> {
             let caller = self.env().caller();
 
             // check if caller is the manager
-            if caller != self.manager_id {
+            if !self.is_authorized(caller, RoleKind::Manager) {
                 return Err(Error::NotManagerId);
             }
 
-            // check if manager balance is equal to total supply
-            let manager_balance = self.balances.get(&self.manager_id).unwrap_or(0);
-            if manager_balance != self.total {
-                return Err(Error::NotAllAssetsOwnedByManager);
+            self.finality_delay = delay;
+
+            Ok(())
+        }
+
+        /// Commits a transfer of `amount` tokens to `receiver`, to be executed by
+        /// `confirm_large_transfer` within the confirmation window.
+        /// Note: a new commit replaces any pending transfer of the caller.
+        /// Note: the transfer can't be confirmed before the finality delay has passed.
+        #[ink(message)]
+        pub fn commit_large_transfer(
+            &mut self,
+            receiver: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let sender = self.env().caller();
+
+            // check if receiver is the zero address, reserved for mint and burn events
+            if receiver == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            // check if amount is non zero
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            let final_at = self
+                .env()
+                .block_number()
+                .checked_add(self.finality_delay)
+                .ok_or(Error::Overflow)?;
+            let expires_at = final_at
+                .checked_add(self.large_transfer_window)
+                .ok_or(Error::Overflow)?;
+            self.pending_large_transfers
+                .insert(sender, &(receiver, amount, final_at, expires_at));
+
+            Ok(())
+        }
+
+        /// Executes the transfer previously committed by the caller.
+        /// Note: if the finality delay hasn't passed yet, TransferNotFinal is returned.
+        /// Note: if the confirmation window has passed, TransferExpired is returned.
+        #[ink(message)]
+        pub fn confirm_large_transfer(&mut self) -> Result<(), Error> {
+            let sender = self.env().caller();
+
+            let (receiver, amount, final_at, expires_at) = self
+                .pending_large_transfers
+                .get(sender)
+                .ok_or(Error::NoPendingTransfer)?;
+
+            // check if the commit is final
+            if self.env().block_number() < final_at {
+                return Err(Error::TransferNotFinal);
+            }
+
+            // check if the commit has expired
+            if self.env().block_number() > expires_at {
+                return Err(Error::TransferExpired);
+            }
+
+            self.transfer_from_to(sender, receiver, amount, Authorization::Confirmed)?;
+            self.pending_large_transfers.remove(sender);
+            self.notify_transfer_hook(sender, receiver, amount);
+
+            Ok(())
+        }
+
+        /// Transfer `amount` of tokens from `from` to `to` on behalf of `from`.
+        /// Note: the caller must have been approved for at least `amount` by `from`.
+        /// Note: the balance of `from` is checked first, so a transfer failing on balance
+        /// never consumes allowance.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let spender = self.env().caller();
+
+            // check if owner has enough balance
+            if self.balances.get(from).unwrap_or(0) < amount {
+                return Err(Error::NotEnoughBalance);
+            }
+
+            // check if spender has enough allowance
+            let allowance = self.allowance(from, spender);
+            if allowance < amount {
+                return Err(Error::InsufficientAllowance);
+            }
+
+            self.transfer_from_to(from, to, amount, Authorization::Holder)?;
+
+            // update spender's allowance
+            self.allowances
+                .insert((from, spender), &(allowance - amount));
+
+            self.notify_transfer_hook(from, to, amount);
+
+            Ok(())
+        }
+
+        /// Returns the error a `transfer` of `amount` from `from` to `to` would fail with,
+        /// without changing any state.
+        /// Note: a mandatory transfer hook can still reject a transfer this accepts.
+        #[ink(message)]
+        pub fn can_transfer(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            self.validate_transfer(from, to, amount, Authorization::Holder)?;
+
+            Ok(())
+        }
+
+        /// Allows `spender` to withdraw up to `amount` of the caller's tokens.
+        /// Note: a new approval replaces the current allowance.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, amount: Balance) -> Result<(), Error> {
+            let owner = self.env().caller();
+
+            // check if spender is the zero address
+            if spender == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            self.set_allowance(owner, spender, amount);
+
+            Ok(())
+        }
+
+        /// Raises the allowance of `spender` over the caller's tokens by `delta`.
+        /// Note: unlike `approve`, this can't race with a `transfer_from` spending the
+        /// current allowance.
+        #[ink(message)]
+        pub fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta: Balance,
+        ) -> Result<(), Error> {
+            let owner = self.env().caller();
+
+            // check if spender is the zero address
+            if spender == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            let allowance = self
+                .allowance(owner, spender)
+                .checked_add(delta)
+                .ok_or(Error::Overflow)?;
+            self.set_allowance(owner, spender, allowance);
+
+            Ok(())
+        }
+
+        /// Lowers the allowance of `spender` over the caller's tokens by `delta`.
+        /// Note: returns InsufficientAllowance if `delta` exceeds the current allowance.
+        #[ink(message)]
+        pub fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta: Balance,
+        ) -> Result<(), Error> {
+            let owner = self.env().caller();
+
+            let allowance = self
+                .allowance(owner, spender)
+                .checked_sub(delta)
+                .ok_or(Error::InsufficientAllowance)?;
+            self.set_allowance(owner, spender, allowance);
+
+            Ok(())
+        }
+
+        /// Distributes `total_amount` of the reserve's tokens to `holders`, pro rata to
+        /// their current balances.
+        /// Note: only the reserve address can distribute tokens.
+        /// Note: the rounding remainder goes to the last holder, so the full amount is distributed.
+        /// Note: each share is paid to the payout address of its holder.
+        /// Note: at most `MAX_BATCH` holders can be paid at once, BatchTooLarge is returned otherwise.
+        #[ink(message)]
+        pub fn distribute(
+            &mut self,
+            total_amount: Balance,
+            holders: Vec<AccountId>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the reserve address
+            if !self.is_authorized(caller, RoleKind::Reserve) {
+                return Err(Error::NotReserveId);
+            }
+
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if batch is small enough
+            if holders.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+
+            // check if there is anything to distribute
+            if total_amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if reserve has enough balance
+            let reserve_remaining = self
+                .balances
+                .get(self.reserve_id)
+                .unwrap_or(0)
+                .checked_sub(total_amount)
+                .ok_or(Error::NotEnoughBalance)?;
+
+            // check if reserve keeps its minimum balance
+            self.ensure_min_reserve(self.reserve_id, reserve_remaining)?;
+
+            // check if every holder is listed once, and both it and its payout address
+            // are opted in and not frozen
+            self.ensure_unique_recipients(&holders)?;
+            let mut holders_balance: Balance = 0;
+            for holder in &holders {
+                self.ensure_can_receive(*holder)?;
+                self.ensure_can_receive(self.payout_address_of(*holder))?;
+
+                holders_balance = holders_balance
+                    .checked_add(self.balances.get(holder).unwrap_or(0))
+                    .ok_or(Error::Overflow)?;
+            }
+            if holders_balance == 0 {
+                return Err(Error::NoHolders);
+            }
+
+            // compute every share before any balance changes
+            let mut shares = Vec::with_capacity(holders.len());
+            let mut distributed: Balance = 0;
+            for (index, holder) in holders.iter().enumerate() {
+                let share = if index == holders.len() - 1 {
+                    total_amount - distributed
+                } else {
+                    total_amount
+                        .checked_mul(self.balances.get(holder).unwrap_or(0))
+                        .ok_or(Error::Overflow)?
+                        / holders_balance
+                };
+                distributed += share;
+                shares.push((self.payout_address_of(*holder), share));
+            }
+
+            // update reserve and holders balances
+            for (payout_address, share) in &shares {
+                self.move_balance(self.reserve_id, *payout_address, *share)?;
+            }
+
+            // emit transfer events
+            for (payout_address, share) in shares {
+                self.emit(
+                    self.reserve_id,
+                    Transfer {
+                        sender: self.reserve_id,
+                        receiver: payout_address,
+                        asset_id: self.asset_id(),
+                        amount: share,
+                        note: Vec::new(),
+                    },
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Transfers `amount` of tokens from the caller to `receiver`, attaching `note` to the
+        /// `Transfer` event, e.g. as a reference for exchanges to reconcile deposits.
+        /// Note: the note can be at most `MAX_NOTE_LEN` bytes, NoteTooLong is returned otherwise.
+        /// Note: otherwise behaves like `transfer`.
+        #[ink(message)]
+        pub fn transfer_with_note(
+            &mut self,
+            receiver: AccountId,
+            amount: Balance,
+            note: Vec<u8>,
+        ) -> Result<(), Error> {
+            let sender = self.env().caller();
+
+            // check if the note fits
+            if note.len() > MAX_NOTE_LEN {
+                return Err(Error::NoteTooLong);
+            }
+
+            self.transfer_noted(sender, receiver, amount, note, Authorization::Holder)?;
+            self.notify_transfer_hook(sender, receiver, amount);
+
+            Ok(())
+        }
+
+        /// Transfers tokens from the caller to each receiver of `transfers`.
+        /// Note: the whole batch is validated before any balance changes, so either every
+        /// transfer is applied or none is.
+        /// Note: every receiver must be opted in, not frozen and listed only once.
+        /// Note: like `transfer`, no account can be left with a dust balance.
+        /// Note: each transfer pays the current transfer fee to the reserve.
+        /// Note: the fees of the whole batch are moved to the reserve at once.
+        /// Note: at most `MAX_BATCH` transfers can be sent at once, BatchTooLarge is returned otherwise.
+        /// Note: a batch whose total is above the large transfer threshold fails with
+        /// ConfirmationRequired, like a single `transfer`.
+        #[ink(message)]
+        pub fn batch_transfer(
+            &mut self,
+            transfers: Vec<(AccountId, Balance)>,
+        ) -> Result<(), Error> {
+            let sender = self.env().caller();
+
+            // check if batch is small enough
+            if transfers.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+
+            // check if the whole batch can leave the sender
+            let total_amount = Self::checked_sum(transfers.iter().map(|(_, amount)| *amount))?;
+            let sender_remaining =
+                self.validate_send(sender, total_amount, Authorization::Holder)?;
+
+            // check if every receiver is listed once, opted in and not frozen
+            let receivers: Vec<AccountId> =
+                transfers.iter().map(|(receiver, _)| *receiver).collect();
+            self.ensure_unique_recipients(&receivers)?;
+            for receiver in &receivers {
+                self.ensure_can_receive(*receiver)?;
+            }
+
+            // compute every fee once, at the current rate
+            let fee_bps = self.current_fee_bps();
+            let fees: Vec<Balance> = transfers
+                .iter()
+                .map(|(receiver, amount)| self.transfer_fee(sender, *receiver, *amount, fee_bps))
+                .collect();
+            let total_fees = Self::checked_sum(fees.iter().copied())?;
+
+            // check if reserve keeps its minimum balance
+            self.ensure_min_reserve(sender, sender_remaining)?;
+
+            // check if neither the sender nor a receiver is left with a dust balance
+            self.ensure_min_balance(sender_remaining)?;
+            for ((receiver, amount), fee) in transfers.iter().zip(&fees) {
+                if *receiver != sender {
+                    let receiver_balance = self
+                        .balances
+                        .get(receiver)
+                        .unwrap_or(0)
+                        .checked_add(amount - fee)
+                        .ok_or(Error::Overflow)?;
+                    self.ensure_min_balance(receiver_balance)?;
+                }
+            }
+
+            // check if reserve can receive the fees
+            if total_fees > 0 && !self.accounts_opted_in.get(self.reserve_id).unwrap_or(false) {
+                return Err(Error::NotOptedIn);
+            }
+
+            // move each transfer, then the fees of the whole batch
+            self.record_send(sender, Authorization::Holder);
+            for ((receiver, amount), fee) in transfers.iter().zip(&fees) {
+                self.move_balance(sender, *receiver, amount - fee)?;
+            }
+            if total_fees > 0 {
+                self.move_balance(sender, self.reserve_id, total_fees)?;
+            }
+
+            // emit transfer events
+            for ((receiver, amount), fee) in transfers.into_iter().zip(fees) {
+                self.emit(
+                    sender,
+                    Transfer {
+                        sender,
+                        receiver,
+                        asset_id: self.asset_id(),
+                        amount: amount - fee,
+                        note: Vec::new(),
+                    },
+                );
+                if fee > 0 {
+                    self.emit(
+                        sender,
+                        Transfer {
+                            sender,
+                            receiver: self.reserve_id,
+                            asset_id: self.asset_id(),
+                            amount: fee,
+                            note: Vec::new(),
+                        },
+                    );
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Releases `amount` of the reserve's own tokens to `to`.
+        /// Note: only the reserve address can release tokens.
+        /// Note: unlike `mint`, releasing moves already issued tokens, so the issued supply
+        /// never grows beyond `total`.
+        #[ink(message)]
+        pub fn release(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the reserve address
+            if !self.is_authorized(caller, RoleKind::Reserve) {
+                return Err(Error::NotReserveId);
+            }
+
+            // check if receiver is opted in and not frozen
+            self.ensure_can_receive(to)?;
+
+            self.transfer_from_to(self.reserve_id, to, amount, Authorization::Authority)
+        }
+
+        /// Mints `amount` of new tokens to `to`.
+        /// Note: only the reserve address can mint tokens.
+        /// Note: the lifetime minted supply, including the initial supply, can never exceed
+        /// the max supply, SupplyCapExceeded is returned otherwise.
+        /// Note: burning doesn't free up mint headroom.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the reserve address
+            if !self.is_authorized(caller, RoleKind::Reserve) {
+                return Err(Error::NotReserveId);
+            }
+
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if supply is locked
+            if self.supply_locked {
+                return Err(Error::SupplyLocked);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if receiver is the zero address, the sender of mint events
+            if to == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            // check if receiver has opted in
+            let receiver_opted_in = self.accounts_opted_in.get(to).unwrap_or(false);
+            if !receiver_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if lifetime minted supply stays within the max supply
+            let minted = self
+                .minted
+                .checked_add(amount)
+                .ok_or(Error::SupplyCapExceeded)?;
+            if self
+                .max_supply
+                .is_some_and(|max_supply| minted > max_supply)
+            {
+                return Err(Error::SupplyCapExceeded);
+            }
+
+            // update receiver balance, received total, issued and minted supply
+            self.record_received(to, amount)?;
+            self.minted = minted;
+            self.issued += amount;
+            self.set_balance(to, self.balances.get(to).unwrap_or(0) + amount);
+
+            // emit transfer event
+            self.emit(
+                to,
+                Transfer {
+                    sender: AccountId::from([0x0; 32]),
+                    receiver: to,
+                    asset_id: self.asset_id(),
+                    amount,
+                    note: Vec::new(),
+                },
+            );
+            self.emit(
+                to,
+                Mint {
+                    asset_id: self.asset_id(),
+                    to,
+                    amount,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Mint, to);
+
+            Ok(())
+        }
+
+        /// Burns `amount` of the caller's tokens.
+        #[ink(message)]
+        pub fn burn(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.burn_tokens(caller, amount)
+        }
+
+        /// Burns `amount` of the tokens of `account`, e.g. to retire tokens bought back
+        /// off-chain.
+        /// Note: only the reserve address can burn tokens from another account, and only
+        /// up to the allowance `account` approved it for, which the burn spends.
+        /// Note: the manager can't burn, as the reserve is the only authority over the
+        /// supply, like for `mint`. The manager can grant itself the reserve role instead.
+        #[ink(message)]
+        pub fn burn_from(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the reserve address
+            if !self.is_authorized(caller, RoleKind::Reserve) {
+                return Err(Error::NotReserveId);
+            }
+
+            // check if reserve has enough allowance
+            let allowance = self.allowance(account, caller);
+            if allowance < amount {
+                return Err(Error::InsufficientAllowance);
+            }
+
+            self.burn_tokens(account, amount)?;
+
+            // update reserve's allowance
+            self.allowances
+                .insert((account, caller), &(allowance - amount));
+
+            // record privileged action
+            self.log_action(AuditAction::Burn, account);
+
+            Ok(())
+        }
+
+        /// Burns `amount` of the tokens of `account` and emits `Transfer` to the zero address
+        /// and `Burn` events.
+        fn burn_tokens(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if supply is locked
+            if self.supply_locked {
+                return Err(Error::SupplyLocked);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if account has enough balance
+            let remaining = self
+                .balances
+                .get(account)
+                .unwrap_or(0)
+                .checked_sub(amount)
+                .ok_or(Error::NotEnoughBalance)?;
+
+            // check if burn dips into locked balance
+            if remaining < self.locked_balance(account) {
+                return Err(Error::BalanceLocked);
+            }
+
+            // check if account is frozen, so frozen holdings stay available to the clawback
+            if self.is_frozen_for_transfer(account) {
+                return Err(Error::FrozenAccount);
+            }
+
+            // check if account is left with a dust balance
+            self.ensure_min_balance(remaining)?;
+
+            // update account balance and issued supply
+            self.issued = self.issued.checked_sub(amount).ok_or(Error::Overflow)?;
+            self.set_balance(account, remaining);
+
+            // emit transfer event
+            self.emit(
+                account,
+                Transfer {
+                    sender: account,
+                    receiver: AccountId::from([0x0; 32]),
+                    asset_id: self.asset_id(),
+                    amount,
+                    note: Vec::new(),
+                },
+            );
+            self.emit(
+                account,
+                Burn {
+                    asset_id: self.asset_id(),
+                    from: account,
+                    amount,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Returns `amount` of the caller's tokens to the reserve, e.g. for a buy-back settled
+        /// off-chain. Unlike `burn`, the tokens stay in the supply and can be reissued.
+        /// Note: redeems are free of the transfer fee.
+        #[ink(message)]
+        pub fn redeem(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if amount is non zero
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if caller and reserve have opted in
+            if !self.accounts_opted_in.get(caller).unwrap_or(false)
+                || !self.accounts_opted_in.get(self.reserve_id).unwrap_or(false)
+            {
+                return Err(Error::NotOptedIn);
+            }
+
+            let caller_remaining = self.validate_send(caller, amount, Authorization::Holder)?;
+
+            // check if caller is left with a dust balance
+            self.ensure_min_balance(caller_remaining)?;
+
+            let redeemed_total = self
+                .redeemed_total
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+
+            // update caller and reserve balances
+            self.move_balance(caller, self.reserve_id, amount)?;
+            self.record_send(caller, Authorization::Holder);
+            self.redeemed_total = redeemed_total;
+
+            // emit transfer and redeem events
+            self.emit(
+                caller,
+                Transfer {
+                    sender: caller,
+                    receiver: self.reserve_id,
+                    asset_id: self.asset_id(),
+                    amount,
+                    note: Vec::new(),
+                },
+            );
+            self.emit(
+                caller,
+                Redeem {
+                    asset_id: self.asset_id(),
+                    holder: caller,
+                    amount,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Returns the escrowed balance `account` can claim once opted in.
+        #[ink(message)]
+        pub fn pending_balance(&self, account: AccountId) -> Balance {
+            self.pending.get(account).unwrap_or(0)
+        }
+
+        /// Moves the caller's escrowed balance into its balance.
+        /// Note: the caller must have opted in first, and not be frozen.
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if caller is opted in and not frozen
+            self.ensure_can_receive(caller)?;
+
+            // check if there is anything to claim
+            let pending = self.pending.get(caller).unwrap_or(0);
+            if pending == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if caller isn't left with a dust balance
+            let caller_balance = self.balances.get(caller).unwrap_or(0);
+            self.ensure_min_balance(caller_balance + pending)?;
+
+            // update caller balance and received total
+            self.record_received(caller, pending)?;
+            self.pending.remove(caller);
+            self.pending_since.remove(caller);
+            self.sum_balances -= pending;
+            self.set_balance(caller, caller_balance + pending);
+
+            // emit transfer event
+            let escrow = self.escrow_account();
+            self.emit(
+                escrow,
+                Transfer {
+                    sender: escrow,
+                    receiver: caller,
+                    asset_id: self.asset_id(),
+                    amount: pending,
+                    note: Vec::new(),
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Returns how many milliseconds an escrowed balance stays claimable before the
+        /// manager can reclaim it.
+        #[ink(message)]
+        pub fn escrow_deadline(&self) -> Timestamp {
+            self.escrow_deadline
+        }
+
+        /// Sets how many milliseconds an escrowed balance stays claimable before the
+        /// manager can reclaim it.
+        /// Note: only the manager can set the escrow deadline.
+        #[ink(message)]
+        pub fn set_escrow_deadline(&mut self, deadline: Timestamp) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.escrow_deadline = deadline;
+
+            Ok(())
+        }
+
+        /// Moves the escrowed balance of `account` back to the reserve.
+        /// Note: only the manager can reclaim escrowed balances, once the escrow deadline
+        /// has passed since the first unclaimed transfer to `account`.
+        #[ink(message)]
+        pub fn reclaim_pending(&mut self, account: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if there is anything to reclaim
+            let pending = self.pending.get(account).unwrap_or(0);
+            if pending == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if the escrow deadline has passed
+            let escrowed_at = self.pending_since.get(account).unwrap_or(0);
+            if self.env().block_timestamp() <= escrowed_at.saturating_add(self.escrow_deadline) {
+                return Err(Error::EscrowNotExpired);
+            }
+
+            // check if reserve can receive the escrowed balance
+            if !self.accounts_opted_in.get(self.reserve_id).unwrap_or(false) {
+                return Err(Error::NotOptedIn);
+            }
+
+            // update reserve balance
+            self.pending.remove(account);
+            self.pending_since.remove(account);
+            self.sum_balances -= pending;
+            let reserve_balance = self.balances.get(self.reserve_id).unwrap_or(0);
+            self.set_balance(self.reserve_id, reserve_balance + pending);
+
+            // emit transfer event
+            let escrow = self.escrow_account();
+            self.emit(
+                escrow,
+                Transfer {
+                    sender: escrow,
+                    receiver: self.reserve_id,
+                    asset_id: self.asset_id(),
+                    amount: pending,
+                    note: Vec::new(),
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Returns the amount `from` has assigned to `to` and `to` hasn't pulled yet.
+        #[ink(message)]
+        pub fn assigned(&self, from: AccountId, to: AccountId) -> Balance {
+            self.assignments.get((from, to)).unwrap_or(0)
+        }
+
+        /// Moves `amount` of the caller's tokens into an assignment `to` can pull.
+        /// Note: `to` doesn't need to be opted in until it pulls.
+        /// Note: the caller is checked like the sender of a `transfer`, and the current
+        /// transfer fee is deducted from `amount` and credited to the reserve.
+        #[ink(message)]
+        pub fn assign(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if there is anything to assign
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if assignment can leave the caller
+            let caller_remaining = self.validate_send(caller, amount, Authorization::Holder)?;
+            let fee = self.transfer_fee(caller, to, amount, self.current_fee_bps());
+
+            // check if reserve keeps its minimum balance
+            self.ensure_min_reserve(caller, caller_remaining)?;
+
+            // check if caller isn't left with a dust balance
+            self.ensure_min_balance(caller_remaining)?;
+
+            // check if reserve can receive the fee
+            if fee > 0 && !self.accounts_opted_in.get(self.reserve_id).unwrap_or(false) {
+                return Err(Error::NotOptedIn);
+            }
+
+            // update caller balance and assignment
+            self.record_send(caller, Authorization::Holder);
+            self.set_balance(caller, caller_remaining + fee);
+            if fee > 0 {
+                self.move_balance(caller, self.reserve_id, fee)?;
+            }
+            let assigned = self.assigned(caller, to);
+            self.assignments
+                .insert((caller, to), &(assigned + amount - fee));
+            self.sum_balances += amount - fee;
+
+            // emit transfer events, assigned tokens are held by the escrow account
+            self.emit(
+                caller,
+                Transfer {
+                    sender: caller,
+                    receiver: self.escrow_account(),
+                    asset_id: self.asset_id(),
+                    amount: amount - fee,
+                    note: Vec::new(),
+                },
+            );
+            if fee > 0 {
+                self.emit(
+                    caller,
+                    Transfer {
+                        sender: caller,
+                        receiver: self.reserve_id,
+                        asset_id: self.asset_id(),
+                        amount: fee,
+                        note: Vec::new(),
+                    },
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Moves everything `from` has assigned to the caller into the caller's balance.
+        /// Note: the caller must be opted in and not frozen, and `from` must not be frozen.
+        #[ink(message)]
+        pub fn pull(&mut self, from: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if caller is opted in and not frozen
+            self.ensure_can_receive(caller)?;
+
+            // check if assigner is frozen
+            if self.is_frozen_for_transfer(from) {
+                return Err(Error::FrozenAccount);
+            }
+
+            // check if there is anything to pull
+            let assigned = self.assigned(from, caller);
+            if assigned == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if caller isn't left with a dust balance
+            let caller_balance = self.balances.get(caller).unwrap_or(0);
+            self.ensure_min_balance(caller_balance + assigned)?;
+
+            // update caller balance and received total
+            self.record_received(caller, assigned)?;
+            self.assignments.remove((from, caller));
+            self.sum_balances -= assigned;
+            self.set_balance(caller, caller_balance + assigned);
+
+            // emit transfer event
+            let escrow = self.escrow_account();
+            self.emit(
+                escrow,
+                Transfer {
+                    sender: escrow,
+                    receiver: caller,
+                    asset_id: self.asset_id(),
+                    amount: assigned,
+                    note: Vec::new(),
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Returns the caller's unpulled assignment to `to` into the caller's balance.
+        #[ink(message)]
+        pub fn cancel_assignment(&mut self, to: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if there is anything to cancel
+            let assigned = self.assigned(caller, to);
+            if assigned == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // update caller balance
+            self.assignments.remove((caller, to));
+            self.sum_balances -= assigned;
+            let caller_balance = self.balances.get(caller).unwrap_or(0);
+            self.set_balance(caller, caller_balance + assigned);
+
+            // emit transfer event
+            let escrow = self.escrow_account();
+            self.emit(
+                escrow,
+                Transfer {
+                    sender: escrow,
+                    receiver: caller,
+                    asset_id: self.asset_id(),
+                    amount: assigned,
+                    note: Vec::new(),
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Opts the caller in and registers `payout_address` as the destination of its
+        /// distribution shares.
+        #[ink(message)]
+        pub fn opt_in_with_payout(&mut self, payout_address: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.opt_in_account(caller)?;
+            self.set_payout_address(Some(payout_address))
+        }
+
+        /// Returns the account distribution shares of `account` are paid to.
+        /// Note: accounts without a payout address are paid directly.
+        #[ink(message)]
+        pub fn payout_address_of(&self, account: AccountId) -> AccountId {
+            self.payout_addresses.get(account).unwrap_or(account)
+        }
+
+        /// Sets the account the caller's distribution shares are paid to.
+        /// Note: `None` resets the payout address to the caller itself.
+        #[ink(message)]
+        pub fn set_payout_address(
+            &mut self,
+            payout_address: Option<AccountId>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            match payout_address {
+                Some(payout_address) if payout_address != caller => {
+                    self.payout_addresses.insert(caller, &payout_address)
+                }
+                _ => self.payout_addresses.remove(caller),
+            }
+
+            Ok(())
+        }
+
+        /// Opts `account` in to this asset on its behalf, e.g. ahead of an airdrop.
+        /// Note: only the manager can force an opt-in.
+        #[ink(message)]
+        pub fn force_opt_in(&mut self, account: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.opt_in_account(account)
+        }
+
+        // OptOut of receiving an asset
+        // Note: the caller must hold no tokens, use `opt_out_to` to close out a balance
+        #[ink(message)]
+        pub fn opt_out(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller has opted in
+            let caller_opted_in = self.accounts_opted_in.get(caller).unwrap_or(false);
+            if !caller_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if caller still holds tokens
+            if self.balances.get(caller).unwrap_or(0) > 0 {
+                return Err(Error::NonZeroBalance);
+            }
+
+            self.opt_out_account(caller);
+
+            Ok(())
+        }
+
+        /// Opts out `account` if it opted in more than `max_age` milliseconds ago
+        /// and holds no tokens.
+        /// Note: only the manager can prune opt-ins.
+        #[ink(message)]
+        pub fn prune_opt_in(
+            &mut self,
+            account: AccountId,
+            max_age: Timestamp,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if account has opted in
+            let account_opted_in = self.accounts_opted_in.get(account).unwrap_or(false);
+            if !account_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if account still holds tokens
+            if self.balances.get(account).unwrap_or(0) > 0 {
+                return Err(Error::NonZeroBalance);
+            }
+
+            // check if the opt-in is old enough
+            let opted_in_at = self.opt_in_ts.get(account).unwrap_or(0);
+            if self.env().block_timestamp().saturating_sub(opted_in_at) <= max_age {
+                return Err(Error::OptInTooRecent);
+            }
+
+            self.opt_out_account(account);
+
+            Ok(())
+        }
+
+        /// Returns the timestamp at which `account` opted in, if it has.
+        #[ink(message)]
+        pub fn opted_in_at(&self, account: AccountId) -> Option<Timestamp> {
+            self.opt_in_ts.get(account)
+        }
+
+        /// Moves the caller's full balance to `close_to`, then opts the caller out.
+        /// Note: `close_to` must have opted in, e.g. the reserve.
+        #[ink(message)]
+        pub fn opt_out_to(&mut self, close_to: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller has opted in
+            let caller_opted_in = self.accounts_opted_in.get(caller).unwrap_or(false);
+            if !caller_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if close to account has opted in
+            let close_to_opted_in = self.accounts_opted_in.get(close_to).unwrap_or(false);
+            if !close_to_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // move remaining balance
+            let caller_balance = self.balances.get(caller).unwrap_or(0);
+            if caller_balance > 0 {
+                // check if the balance would stay with the caller
+                if close_to == caller {
+                    return Err(Error::NonZeroBalance);
+                }
+
+                self.transfer_from_to(caller, close_to, caller_balance, Authorization::Holder)?;
+            }
+
+            self.opt_out_account(caller);
+
+            Ok(())
+        }
+
+        // Freeze an account
+        // Note: `reason` is a compliance reason code stored with the freeze, unfreezing resets it to 0
+        #[ink(message)]
+        pub fn freeze(
+            &mut self,
+            account: AccountId,
+            freeze: bool,
+            reason: u8,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if token can be frozen
+            if !self.freezable {
+                return Err(Error::NotFreezable);
+            }
+
+            // check if freezing has been renounced
+            if self.freeze_renounced {
+                return Err(Error::FreezeRenounced);
+            }
+
+            // check if caller is the freeze address
+            if !self.is_authorized(caller, RoleKind::Freeze) {
+                return Err(Error::NotFreezeId);
+            }
+
+            // check if a freeze of the reserve must wait for the timelock
+            if account == self.reserve_id && self.reconfig_delay > 0 {
+                return Err(Error::ApprovalRequired);
+            }
+
+            // check if account is already in the requested state
+            let account_frozen = self.is_frozen(account);
+            if account_frozen && freeze {
+                return Err(Error::AlreadyFrozen);
+            }
+            if !account_frozen && !freeze {
+                return Err(Error::NotFrozen);
+            }
+
+            self.apply_freeze(account, freeze, reason);
+
+            Ok(())
+        }
+
+        /// Freezes or unfreezes the reserve once a `FreezeReserve` action has passed its timelock.
+        fn apply_reserve_freeze(&mut self, freeze: bool) -> Result<(), Error> {
+            // check if token can be frozen
+            if !self.freezable {
+                return Err(Error::NotFreezable);
+            }
+
+            // check if freezing has been renounced
+            if self.freeze_renounced {
+                return Err(Error::FreezeRenounced);
+            }
+
+            // check if reserve is already in the requested state
+            let reserve_frozen = self.is_frozen(self.reserve_id);
+            if reserve_frozen && freeze {
+                return Err(Error::AlreadyFrozen);
+            }
+            if !reserve_frozen && !freeze {
+                return Err(Error::NotFrozen);
+            }
+
+            self.apply_freeze(self.reserve_id, freeze, 0);
+
+            Ok(())
+        }
+
+        /// Applies the same frozen status to every account in `accounts`.
+        /// Note: only the freeze address can freeze accounts, at most `MAX_BATCH` at once,
+        /// BatchTooLarge is returned otherwise.
+        /// Note: accounts already in the requested state are skipped.
+        #[ink(message)]
+        pub fn freeze_many(&mut self, accounts: Vec<AccountId>, freeze: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if token can be frozen
+            if !self.freezable {
+                return Err(Error::NotFreezable);
+            }
+
+            // check if freezing has been renounced
+            if self.freeze_renounced {
+                return Err(Error::FreezeRenounced);
+            }
+
+            // check if caller is the freeze address
+            if !self.is_authorized(caller, RoleKind::Freeze) {
+                return Err(Error::NotFreezeId);
+            }
+
+            // check if batch is small enough
+            if accounts.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+
+            // check if a freeze of the reserve must wait for the timelock
+            if self.reconfig_delay > 0
+                && accounts.contains(&self.reserve_id)
+                && self.is_frozen(self.reserve_id) != freeze
+            {
+                return Err(Error::ApprovalRequired);
+            }
+
+            for account in accounts {
+                // skip accounts already in the requested state
+                if self.is_frozen(account) != freeze {
+                    self.apply_freeze(account, freeze, 0);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Sets the frozen status and reason of `account`, then emits and logs the change.
+        fn apply_freeze(&mut self, account: AccountId, freeze: bool, reason: u8) {
+            let account_frozen = self.is_frozen(account);
+
+            // update account's frozen status and reason
+            self.set_frozen(account, freeze);
+            let reason = if freeze { reason } else { 0 };
+            if reason == 0 {
+                self.freeze_reasons.remove(account);
+            } else {
+                self.freeze_reasons.insert(account, &reason);
+            }
+
+            // emit freeze event
+            self.emit(
+                account,
+                Freeze {
+                    asset_id: self.asset_id(),
+                    account,
+                    freeze,
+                    freeze_id: self.freeze_id,
+                    previous: account_frozen,
+                    reason,
+                },
+            );
+
+            // record privileged action
+            let action = if freeze {
+                AuditAction::Freeze
+            } else {
+                AuditAction::Unfreeze
+            };
+            self.log_action(action, account);
+        }
+
+        /// Returns whether `account` keeps moving tokens while frozen.
+        #[ink(message)]
+        pub fn is_freeze_exempt(&self, account: AccountId) -> bool {
+            self.freeze_exempt.get(account).unwrap_or(false)
+        }
+
+        /// Exempts `account` from the frozen checks on transfers, e.g. for a treasury or
+        /// bridge account that must stay liquid through a compliance hold.
+        /// Note: only the freeze address can set freeze exemptions.
+        /// Note: the frozen status itself is unchanged, `is_frozen` still reports it.
+        #[ink(message)]
+        pub fn set_freeze_exempt(&mut self, account: AccountId, exempt: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if token can be frozen
+            if !self.freezable {
+                return Err(Error::NotFreezable);
+            }
+
+            // check if freezing has been renounced
+            if self.freeze_renounced {
+                return Err(Error::FreezeRenounced);
+            }
+
+            // check if caller is the freeze address
+            if !self.is_authorized(caller, RoleKind::Freeze) {
+                return Err(Error::NotFreezeId);
+            }
+
+            if exempt {
+                self.freeze_exempt.insert(account, &true);
+            } else {
+                self.freeze_exempt.remove(account);
+            }
+
+            Ok(())
+        }
+
+        // Modify/Reconfigure an asset
+        // Note: only the manager can modify an asset
+        // Note: once clawback is renounced, the clawback address can only be the zero address
+        // Note: only mutable asset params can be modified
+        // Note: params passed as None keep their current value, Some of the zero address
+        // clears them, see `clear_role` to clear one for good
+        // Note: with an approval threshold above 1, modifications go through `propose_action`
+        // Note: a new manager is only proposed, see `propose_manager`, clearing it is immediate
+        // List of mutable asset params:
+        // - managerId, reserveId, freezeId, clawbackId
+        #[ink(message)]
+        pub fn modify_asset(
+            &mut self,
+            manager: Option<AccountId>,
+            reserve: Option<AccountId>,
+            freeze: Option<AccountId>,
+            clawback: Option<AccountId>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if authorities can still be changed
+            if self.management_renounced {
+                return Err(Error::ManagementRenounced);
+            }
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_modify(manager, reserve, freeze, clawback)
+        }
+
+        /// Returns the manager proposed by the current manager, if it hasn't accepted yet.
+        #[ink(message)]
+        pub fn pending_manager(&self) -> Option<AccountId> {
+            self.pending_manager
+        }
+
+        /// Proposes `manager` as the new manager, which takes over once it calls `accept_manager`.
+        /// Note: only the manager can propose a new manager, and stays active until then.
+        /// Note: a new proposal replaces the pending one.
+        #[ink(message)]
+        pub fn propose_manager(&mut self, manager: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if authorities can still be changed
+            if self.management_renounced {
+                return Err(Error::ManagementRenounced);
+            }
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            // check if manager is the zero address
+            if manager == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            self.propose(manager);
+
+            Ok(())
+        }
+
+        /// Makes the caller the manager, if the current manager proposed it.
+        #[ink(message)]
+        pub fn accept_manager(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if authorities can still be changed
+            if self.management_renounced {
+                return Err(Error::ManagementRenounced);
+            }
+
+            // check if caller is the proposed manager
+            if self.pending_manager != Some(caller) {
+                return Err(Error::NotManagerId);
+            }
+
+            let previous = self.manager_id;
+            self.manager_id = caller;
+            self.pending_manager = None;
+
+            // emit manager changed event
+            self.emit(
+                caller,
+                ManagerChanged {
+                    asset_id: self.asset_id(),
+                    previous,
+                    manager_id: caller,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, caller);
+
+            Ok(())
+        }
+
+        /// Records `manager` as the pending manager and emits a `ManagerProposed` event.
+        fn propose(&mut self, manager: AccountId) {
+            self.pending_manager = Some(manager);
+
+            // emit manager proposed event
+            self.emit(
+                self.manager_id,
+                ManagerProposed {
+                    asset_id: self.asset_id(),
+                    manager_id: self.manager_id,
+                    proposed: manager,
+                },
+            );
+        }
+
+        /// Returns the contract allowed to act in place of every role, if any.
+        #[ink(message)]
+        pub fn controller(&self) -> Option<AccountId> {
+            self.controller
+        }
+
+        /// Delegates every role to `new_controller`, e.g. a governance or upgrade contract,
+        /// in addition to the accounts holding them.
+        /// Note: only the manager can delegate to a controller.
+        /// Note: `None` clears the controller.
+        /// Note: with an approval threshold above 1 or a reconfiguration delay, delegating
+        /// goes through `propose_action`.
+        #[ink(message)]
+        pub fn delegate_to(&mut self, new_controller: Option<AccountId>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_delegation(new_controller)
+        }
+
+        /// Sets the controller of the asset.
+        fn apply_delegation(&mut self, new_controller: Option<AccountId>) -> Result<(), Error> {
+            // check if authorities can still be changed
+            if self.management_renounced && new_controller.is_some() {
+                return Err(Error::ManagementRenounced);
+            }
+
+            self.controller = new_controller;
+
+            Ok(())
+        }
+
+        /// Returns whether `account` holds `role`, as its authority address or through a grant.
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleKind, account: AccountId) -> bool {
+            let role_account = match role {
+                RoleKind::Manager => Some(self.manager_id),
+                RoleKind::Reserve => Some(self.reserve_id),
+                RoleKind::Freeze => Some(self.freeze_id),
+                RoleKind::Clawback => Some(self.clawback_id),
+                RoleKind::Custom(_) => None,
+            };
+
+            role_account == Some(account) || self.role_grants.get((role, account)).unwrap_or(false)
+        }
+
+        /// Grants `role` to `account`, e.g. to have several freezers.
+        /// Note: only the manager can grant roles.
+        /// Note: the authority address of the role keeps it, renouncing a role still
+        /// blocks its grants.
+        /// Note: with an approval threshold above 1 or a reconfiguration delay, granting
+        /// a role goes through `propose_action`.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleKind, account: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_grant_role(role, account)
+        }
+
+        /// Grants `role` to `account`.
+        fn apply_grant_role(&mut self, role: RoleKind, account: AccountId) -> Result<(), Error> {
+            // check if authorities can still be changed
+            if self.management_renounced {
+                return Err(Error::ManagementRenounced);
+            }
+
+            // check if account is the zero address
+            if account == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            // check if the role has been cleared for good
+            if self.role_cleared(role) {
+                return Err(Self::cleared_error(role));
+            }
+
+            if !self.role_grants.get((role, account)).unwrap_or(false) {
+                self.role_grants.insert((role, account), &true);
+
+                // emit role granted event
+                self.emit(
+                    account,
+                    RoleGranted {
+                        asset_id: self.asset_id(),
+                        role,
+                        account,
+                    },
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Revokes the grant of `role` to `account`.
+        /// Note: only the manager can revoke roles, authority addresses are changed with
+        /// `modify_asset` instead.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: RoleKind, account: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.remove_grant(role, account);
+
+            Ok(())
+        }
+
+        /// Gives up the caller's grant of `role`.
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: RoleKind) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.remove_grant(role, caller);
+
+            Ok(())
+        }
+
+        /// Removes the grant of `role` to `account`, if any, and emits a `RoleRevoked` event.
+        fn remove_grant(&mut self, role: RoleKind, account: AccountId) {
+            if self.role_grants.get((role, account)).unwrap_or(false) {
+                self.role_grants.remove((role, account));
+
+                // emit role revoked event
+                self.emit(
+                    account,
+                    RoleRevoked {
+                        asset_id: self.asset_id(),
+                        role,
+                        account,
+                    },
+                );
+            }
+        }
+
+        /// Returns the co-managers that approve actions together with the manager.
+        #[ink(message)]
+        pub fn co_managers(&self) -> Vec<AccountId> {
+            self.co_managers.clone()
+        }
+
+        /// Returns the number of distinct approvals a proposed action needs to execute.
+        #[ink(message)]
+        pub fn approval_threshold(&self) -> u32 {
+            self.approval_threshold
+        }
+
+        /// Returns the number of approvals collected by the pending action `action_id`.
+        #[ink(message)]
+        pub fn approval_count(&self, action_id: u32) -> u32 {
+            self.approval_counts.get(action_id).unwrap_or(0)
+        }
+
+        /// Proposes `action` and approves it on behalf of the caller.
+        /// Returns the id other managers approve the action with.
+        /// Note: only the manager and co-managers can propose an action, except clawbacks and
+        /// freezes of the reserve, which only the clawback and freeze authorities can propose.
+        #[ink(message)]
+        pub fn propose_action(&mut self, action: PendingAction) -> Result<u32, Error> {
+            let caller = self.env().caller();
+
+            // check if caller can sign the action
+            self.ensure_can_sign(caller, &action)?;
+
+            let action_id = self.proposal_count;
+            self.proposal_count = action_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.proposals.insert(action_id, &action);
+            self.proposal_blocks
+                .insert(action_id, &self.env().block_number());
+
+            self.approve_action(action_id)?;
+
+            Ok(action_id)
+        }
+
+        /// Approves the pending action `action_id`, executing it once it has collected
+        /// `approval_threshold` distinct approvals and the reconfiguration delay has passed.
+        /// Note: only the manager and co-managers can approve an action, each once, and
+        /// only the clawback and freeze authorities can approve clawbacks and freezes of the
+        /// reserve.
+        /// Note: an approved action still in its timelock is executed with `execute_action`.
+        #[ink(message)]
+        pub fn approve_action(&mut self, action_id: u32) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if action is pending
+            let action = self.proposals.get(action_id).ok_or(Error::NoSuchProposal)?;
+
+            // check if caller can sign the action
+            self.ensure_can_sign(caller, &action)?;
+
+            // check if caller has already approved
+            if self.approvals.get((action_id, caller)).unwrap_or(false) {
+                return Err(Error::AlreadyApproved);
+            }
+
+            self.approvals.insert((action_id, caller), &true);
+            let approval_count = self.approval_count(action_id) + 1;
+            self.approval_counts.insert(action_id, &approval_count);
+
+            // execute action once the threshold is reached and the timelock has passed
+            if approval_count < self.approval_threshold || !self.timelock_passed(action_id) {
+                return Ok(());
+            }
+            self.execute(action_id, action)
+        }
+
+        /// Executes the pending action `action_id` once it has collected `approval_threshold`
+        /// approvals and the reconfiguration delay has passed since it was proposed.
+        /// Note: only the accounts that can approve an action can execute it.
+        #[ink(message)]
+        pub fn execute_action(&mut self, action_id: u32) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if action is pending
+            let action = self.proposals.get(action_id).ok_or(Error::NoSuchProposal)?;
+
+            // check if caller can sign the action
+            self.ensure_can_sign(caller, &action)?;
+
+            // check if action has collected enough approvals
+            if self.approval_count(action_id) < self.approval_threshold {
+                return Err(Error::ApprovalRequired);
+            }
+
+            // check if the timelock has passed
+            if !self.timelock_passed(action_id) {
+                return Err(Error::TimelockActive);
+            }
+
+            self.execute(action_id, action)
+        }
+
+        /// Drops the pending action `action_id`, e.g. after holders raised concerns during
+        /// its timelock.
+        /// Note: only the manager can cancel a pending action.
+        #[ink(message)]
+        pub fn cancel_pending(&mut self, action_id: u32) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if action is pending
+            if self.proposals.get(action_id).is_none() {
+                return Err(Error::NoSuchProposal);
+            }
+
+            self.proposals.remove(action_id);
+            self.proposal_blocks.remove(action_id);
+
+            Ok(())
+        }
+
+        /// Returns the number of blocks a proposed action waits before it can execute.
+        #[ink(message)]
+        pub fn reconfig_delay(&self) -> BlockNumber {
+            self.reconfig_delay
+        }
+
+        /// Sets the number of blocks a proposed modification, destruction or freeze of the
+        /// reserve waits before it can execute, giving holders advance warning.
+        /// Note: only the manager can set the delay.
+        /// Note: once a delay is set, modifying, destroying and freezing the reserve, as well as
+        /// changing the delay, go through `propose_action`.
+        #[ink(message)]
+        pub fn set_reconfig_delay(&mut self, delay: BlockNumber) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.reconfig_delay = delay;
+
+            Ok(())
+        }
+
+        /// Returns whether the action must go through `propose_action`, because co-managers
+        /// must approve it or it is timelocked.
+        fn requires_proposal(&self) -> bool {
+            self.approval_threshold > 1 || self.reconfig_delay > 0
+        }
+
+        /// Returns whether the reconfiguration delay has passed since `action_id` was proposed.
+        fn timelock_passed(&self, action_id: u32) -> bool {
+            let proposed_at = self.proposal_blocks.get(action_id).unwrap_or(0);
+            self.env().block_number() >= proposed_at.saturating_add(self.reconfig_delay)
+        }
+
+        /// Removes the pending action `action_id` and applies it.
+        fn execute(&mut self, action_id: u32, action: PendingAction) -> Result<(), Error> {
+            self.proposals.remove(action_id);
+            self.proposal_blocks.remove(action_id);
+            match action {
+                PendingAction::Modify {
+                    manager,
+                    reserve,
+                    freeze,
+                    clawback,
+                } => self.apply_modify(manager, reserve, freeze, clawback),
+                PendingAction::Destroy => self.apply_destroy(),
+                PendingAction::Revoke {
+                    receiver,
+                    target,
+                    amount,
+                } => self.apply_revoke(receiver, target, amount),
+                PendingAction::FreezeReserve { freeze } => self.apply_reserve_freeze(freeze),
+                PendingAction::SetReconfigDelay { delay } => {
+                    self.reconfig_delay = delay;
+                    Ok(())
+                }
+                PendingAction::ClearRole { role } => self.apply_clear_role(role),
+                PendingAction::DelegateTo { controller } => self.apply_delegation(controller),
+                PendingAction::GrantRole { role, account } => self.apply_grant_role(role, account),
+                PendingAction::ForceTransfer { from, to, amount } => {
+                    self.apply_force_transfer(from, to, amount)
+                }
+            }
+        }
+
+        /// Updates the authority addresses of the asset.
+        fn apply_modify(
+            &mut self,
+            manager: Option<AccountId>,
+            reserve: Option<AccountId>,
+            freeze: Option<AccountId>,
+            clawback: Option<AccountId>,
+        ) -> Result<(), Error> {
+            // check if authorities can still be changed
+            if self.management_renounced {
+                return Err(Error::ManagementRenounced);
+            }
+
+            // check if renounced addresses stay the zero address
+            let zero = AccountId::from([0x0; 32]);
+            if self.clawback_renounced && clawback.is_some_and(|clawback| clawback != zero) {
+                return Err(Error::ClawbackRenounced);
+            }
+            if self.freeze_renounced && freeze.is_some_and(|freeze| freeze != zero) {
+                return Err(Error::FreezeRenounced);
+            }
+            if self.reserve_renounced && reserve.is_some_and(|reserve| reserve != zero) {
+                return Err(Error::ReserveRenounced);
+            }
+
+            // update the provided asset params, a new manager has to accept first
+            match manager {
+                Some(manager) if manager == zero => {
+                    self.manager_id = zero;
+                    self.pending_manager = None;
+                }
+                Some(manager) if manager != self.manager_id => self.propose(manager),
+                _ => {}
+            }
+            if let Some(reserve) = reserve {
+                self.reserve_id = reserve;
+            }
+            if let Some(freeze) = freeze {
+                self.freeze_id = freeze;
+            }
+            if let Some(clawback) = clawback {
+                self.clawback_id = clawback;
+            }
+
+            // emit modify asset event
+            self.emit_modify();
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, self.manager_id);
+
+            Ok(())
+        }
+
+        /// Returns whether the manager can rename the asset.
+        #[ink(message)]
+        pub fn names_mutable(&self) -> bool {
+            self.names_mutable
+        }
+
+        /// Renames the asset, updating only the names that are provided.
+        /// Note: only the manager can rename an asset.
+        /// Note: NamesImmutable is returned if the asset was created with immutable names.
+        #[ink(message)]
+        pub fn rename(
+            &mut self,
+            asset_name: Option<String>,
+            unit_name: Option<String>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if names can be changed
+            if !self.names_mutable {
+                return Err(Error::NamesImmutable);
+            }
+
+            // check if names are not empty
+            if asset_name.as_ref().is_some_and(|name| name.is_empty())
+                || unit_name.as_ref().is_some_and(|name| name.is_empty())
+            {
+                return Err(Error::EmptyName);
+            }
+
+            // update asset names
+            if let Some(asset_name) = asset_name {
+                self.asset_name = asset_name;
+            }
+            if let Some(unit_name) = unit_name {
+                self.unit_name = unit_name;
+            }
+
+            // emit rename event
+            self.emit(
+                self.manager_id,
+                Rename {
+                    manager_id: self.manager_id,
+                    asset_name: self.asset_name.clone(),
+                    unit_name: self.unit_name.clone(),
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, self.manager_id);
+
+            Ok(())
+        }
+
+        /// Updates the URL and the metadata hash of the asset in a single step.
+        /// Note: only the manager can update the metadata.
+        #[ink(message)]
+        pub fn set_metadata(&mut self, url: String, hash: [u8; 4]) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // update metadata params
+            let old_url = core::mem::replace(&mut self.url, url);
+            let old_metadata_hash = core::mem::replace(&mut self.metadata_hash, hash);
+
+            // emit metadata updated event
+            self.emit(
+                self.manager_id,
+                MetadataUpdated {
+                    asset_id: self.asset_id(),
+                    old_url,
+                    new_url: self.url.clone(),
+                    old_metadata_hash,
+                    new_metadata_hash: self.metadata_hash,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, self.manager_id);
+
+            Ok(())
+        }
+
+        /// Returns whether clawback has been renounced for good.
+        #[ink(message)]
+        pub fn clawback_renounced(&self) -> bool {
+            self.clawback_renounced
+        }
+
+        /// Renounces clawback for good, so holders are guaranteed their tokens can't be seized.
+        /// Note: only the manager can renounce clawback.
+        /// Note: afterwards the clawback address stays the zero address, and revoking or
+        /// forcing transfers always fails with ClawbackRenounced.
+        /// Note: with an approval threshold above 1 or a reconfiguration delay, clawback is
+        /// renounced by proposing `PendingAction::ClearRole` for the clawback role.
+        #[ink(message)]
+        pub fn renounce_clawback(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            // check if clawback is already renounced
+            if self.clawback_renounced {
+                return Err(Error::ClawbackRenounced);
+            }
+
+            self.clawback_renounced = true;
+            self.clawback_id = AccountId::from([0x0; 32]);
+
+            // emit modify asset event
+            self.emit_modify();
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, self.clawback_id);
+
+            Ok(())
+        }
+
+        /// Returns whether the freeze authority has been renounced for good.
+        #[ink(message)]
+        pub fn freeze_renounced(&self) -> bool {
+            self.freeze_renounced
+        }
+
+        /// Renounces the freeze authority for good, so no account can be frozen or
+        /// unfrozen anymore.
+        /// Note: only the freeze address can renounce the freeze authority.
+        /// Note: afterwards the freeze address stays the zero address.
+        /// Note: with an approval threshold above 1 or a reconfiguration delay, the freeze
+        /// authority is renounced by proposing `PendingAction::ClearRole` for the freeze role.
+        #[ink(message)]
+        pub fn renounce_freeze(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the freeze address
+            if !self.is_authorized(caller, RoleKind::Freeze) {
+                return Err(Error::NotFreezeId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            // check if freeze authority is already renounced
+            if self.freeze_renounced {
+                return Err(Error::FreezeRenounced);
+            }
+
+            self.freeze_renounced = true;
+            self.freeze_id = AccountId::from([0x0; 32]);
+
+            // emit modify asset event
+            self.emit_modify();
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, self.freeze_id);
+
+            Ok(())
+        }
+
+        /// Returns whether the reserve address has been cleared for good.
+        #[ink(message)]
+        pub fn reserve_renounced(&self) -> bool {
+            self.reserve_renounced
+        }
+
+        /// Clears the authority address of `role` for good, as setting an Algorand asset's
+        /// address to empty locks it forever.
+        /// Note: only the manager can clear roles, and grants of a cleared role no longer
+        /// authorize their holders.
+        /// Note: with an approval threshold above 1 or a reconfiguration delay, clearing
+        /// goes through `propose_action`.
+        /// Note: InvalidRole is returned for custom roles, which have no authority address.
+        #[ink(message)]
+        pub fn clear_role(&mut self, role: RoleKind) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the role has an authority address
+            if let RoleKind::Custom(_) = role {
+                return Err(Error::InvalidRole);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_clear_role(role)
+        }
+
+        /// Clears the authority address of `role` for good.
+        fn apply_clear_role(&mut self, role: RoleKind) -> Result<(), Error> {
+            // check if the role is already cleared
+            if self.role_cleared(role) {
+                return Err(Self::cleared_error(role));
+            }
+
+            let zero = AccountId::from([0x0; 32]);
+            let previous = match role {
+                RoleKind::Manager => {
+                    self.management_renounced = true;
+                    self.pending_manager = None;
+                    self.controller = None;
+                    core::mem::replace(&mut self.manager_id, zero)
+                }
+                RoleKind::Reserve => {
+                    self.reserve_renounced = true;
+                    core::mem::replace(&mut self.reserve_id, zero)
+                }
+                RoleKind::Freeze => {
+                    self.freeze_renounced = true;
+                    core::mem::replace(&mut self.freeze_id, zero)
+                }
+                RoleKind::Clawback => {
+                    self.clawback_renounced = true;
+                    core::mem::replace(&mut self.clawback_id, zero)
+                }
+                RoleKind::Custom(_) => return Err(Error::InvalidRole),
+            };
+
+            // emit role cleared and modify asset events
+            self.emit(
+                previous,
+                RoleCleared {
+                    asset_id: self.asset_id(),
+                    role,
+                    previous,
+                },
+            );
+            self.emit_modify();
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, zero);
+
+            Ok(())
+        }
+
+        /// Returns the error for an authority address that has been cleared for good.
+        fn cleared_error(role: RoleKind) -> Error {
+            match role {
+                RoleKind::Reserve => Error::ReserveRenounced,
+                RoleKind::Freeze => Error::FreezeRenounced,
+                RoleKind::Clawback => Error::ClawbackRenounced,
+                RoleKind::Manager | RoleKind::Custom(_) => Error::ManagementRenounced,
+            }
+        }
+
+        /// Returns whether management has been renounced for good.
+        #[ink(message)]
+        pub fn management_renounced(&self) -> bool {
+            self.management_renounced
+        }
+
+        /// Renounces management for good, so the authorities of the asset can never change again.
+        /// Note: only the manager can renounce management.
+        /// Note: afterwards the manager address stays the zero address and the controller
+        /// is cleared.
+        /// Note: with an approval threshold above 1 or a reconfiguration delay, management is
+        /// renounced by proposing `PendingAction::ClearRole` for the manager role.
+        #[ink(message)]
+        pub fn renounce_management(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.management_renounced = true;
+            self.manager_id = AccountId::from([0x0; 32]);
+            self.pending_manager = None;
+            self.controller = None;
+
+            // emit modify asset event
+            self.emit_modify();
+
+            // record privileged action
+            self.log_action(AuditAction::Modify, self.manager_id);
+
+            Ok(())
+        }
+
+        /// Returns whether clawback is restricted to accounts that are currently opted in.
+        #[ink(message)]
+        pub fn strict_clawback(&self) -> bool {
+            self.strict_clawback
+        }
+
+        /// Restricts clawback to accounts that are currently opted in, or lifts the restriction.
+        /// Note: only the manager can change the clawback strictness.
+        #[ink(message)]
+        pub fn set_strict_clawback(&mut self, strict: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.strict_clawback = strict;
+
+            Ok(())
+        }
+
+        // Revoke an asset
+        // Note: only the clawback address can revoke an asset
+        // Note: must specify amount, revocation target id, and receiver
+        // Note: under strict clawback the revocation target must be opted in
+        // Note: with an approval threshold above 1, revocations go through `propose_action`
+        #[ink(message)]
+        pub fn revoke_asset(
+            &mut self,
+            receiver: AccountId,
+            recovation_target: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if clawback has been renounced
+            if self.clawback_renounced {
+                return Err(Error::ClawbackRenounced);
+            }
+
+            // check if caller is the clawback address
+            if !self.is_authorized(caller, RoleKind::Clawback) {
+                return Err(Error::NotClawbackId);
+            }
+
+            // check if co-managers must approve the clawback
+            if self.approval_threshold > 1 {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_revoke(receiver, recovation_target, amount)
+        }
+
+        /// Moves `amount` of tokens from `recovation_target` to `receiver` and emits a
+        /// `Revoke` event.
+        fn apply_revoke(
+            &mut self,
+            receiver: AccountId,
+            recovation_target: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            // check if clawback has been renounced
+            if self.clawback_renounced {
+                return Err(Error::ClawbackRenounced);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if receiver has opted in
+            let receiver_opted_in = self.accounts_opted_in.get(receiver).unwrap_or(false);
+            if !receiver_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if recovation target has opted in, when clawback is strict
+            if self.strict_clawback
+                && !self
+                    .accounts_opted_in
+                    .get(recovation_target)
+                    .unwrap_or(false)
+            {
+                return Err(Error::NotOptedIn);
+            }
+
+            // update recovation target and receiver balances
+            self.move_balance(recovation_target, receiver, amount)?;
+
+            // emit revoke asset event
+            self.emit(
+                recovation_target,
+                Revoke {
+                    asset_id: self.asset_id(),
+                    from: recovation_target,
+                    amount: Some(amount),
+                    clawback_id: self.clawback_id,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Revoke, recovation_target);
+
+            Ok(())
+        }
+
+        /// Moves `amount` of tokens from `from` to `to`, e.g. to execute a court order.
+        /// Note: only the clawback address can force a transfer.
+        /// Note: unlike `revoke_asset`, both accounts can be any opted in holders, and
+        /// their frozen status is ignored.
+        /// Note: with an approval threshold above 1, forced transfers go through
+        /// `propose_action`.
+        #[ink(message)]
+        pub fn force_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if clawback has been renounced
+            if self.clawback_renounced {
+                return Err(Error::ClawbackRenounced);
+            }
+
+            // check if caller is the clawback address
+            if !self.is_authorized(caller, RoleKind::Clawback) {
+                return Err(Error::NotClawbackId);
+            }
+
+            // check if co-managers must approve the clawback
+            if self.approval_threshold > 1 {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_force_transfer(from, to, amount)
+        }
+
+        /// Moves `amount` of tokens from `from` to `to` and emits a `Transfer` event.
+        fn apply_force_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            // check if clawback has been renounced
+            if self.clawback_renounced {
+                return Err(Error::ClawbackRenounced);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if both accounts have opted in
+            if !self.accounts_opted_in.get(from).unwrap_or(false)
+                || !self.accounts_opted_in.get(to).unwrap_or(false)
+            {
+                return Err(Error::NotOptedIn);
+            }
+
+            // update from and to balances
+            self.move_balance(from, to, amount)?;
+
+            // emit transfer event
+            self.emit(
+                from,
+                Transfer {
+                    sender: from,
+                    receiver: to,
+                    asset_id: self.asset_id(),
+                    amount,
+                    note: Vec::new(),
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::ForceTransfer, from);
+
+            Ok(())
+        }
+
+        /// Locks `amount` of `account`'s balance until `release_ts`.
+        /// Note: only the reserve address can lock balances.
+        /// Note: the locked amount can't exceed the balance of `account`.
+        /// Note: a new lock replaces the current lock of `account`.
+        #[ink(message)]
+        pub fn lock_until(
+            &mut self,
+            account: AccountId,
+            amount: Balance,
+            release_ts: Timestamp,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the reserve address
+            if !self.is_authorized(caller, RoleKind::Reserve) {
+                return Err(Error::NotReserveId);
+            }
+
+            // check if account has enough balance to lock
+            if self.balances.get(account).unwrap_or(0) < amount {
+                return Err(Error::NotEnoughBalance);
+            }
+
+            // replace the current lock
+            let (previous_amount, _) = self.locked.get(account).unwrap_or((0, 0));
+            self.total_locked = self
+                .total_locked
+                .checked_sub(previous_amount)
+                .and_then(|total_locked| total_locked.checked_add(amount))
+                .ok_or(Error::Overflow)?;
+            self.locked.insert(account, &(amount, release_ts));
+
+            Ok(())
+        }
+
+        /// Returns the minimum balance transfers out of the reserve must leave behind.
+        #[ink(message)]
+        pub fn min_reserve(&self) -> Balance {
+            self.min_reserve
+        }
+
+        /// Sets the minimum balance transfers out of the reserve must leave behind.
+        /// Note: only the manager can set the minimum reserve.
+        #[ink(message)]
+        pub fn set_min_reserve(&mut self, min_reserve: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.min_reserve = min_reserve;
+
+            Ok(())
+        }
+
+        /// Returns the smallest nonzero balance a transfer may leave on either side.
+        /// Note: the minimum balance is set at creation and never changes, a full drain to
+        /// zero is always allowed.
+        #[ink(message)]
+        pub fn min_balance(&self) -> Balance {
+            self.min_balance
+        }
+
+        /// Returns the transfer fee in basis points at the current block.
+        /// Note: the fee decays linearly from `fee_start_bps` at `fee_decay_start`
+        /// down to `fee_floor_bps` at `fee_decay_end`.
+        #[ink(message)]
+        pub fn current_fee_bps(&self) -> u16 {
+            let block = self.env().block_number();
+            if block <= self.fee_decay_start {
+                return self.fee_start_bps;
+            }
+            if block >= self.fee_decay_end {
+                return self.fee_floor_bps;
+            }
+
+            let elapsed = (block - self.fee_decay_start) as u64;
+            let duration = (self.fee_decay_end - self.fee_decay_start) as u64;
+            let decay = (self.fee_start_bps - self.fee_floor_bps) as u64 * elapsed / duration;
+            self.fee_start_bps - decay as u16
+        }
+
+        /// Sets a transfer fee that decays linearly from `start_bps` at block `decay_start`
+        /// down to `floor_bps` at block `decay_end`.
+        /// Note: only the manager can set the transfer fee.
+        /// Note: rates above 10000 bps, a floor above the start rate or a decay ending before
+        /// it starts are rejected with InvalidFee.
+        #[ink(message)]
+        pub fn set_fee_decay(
+            &mut self,
+            start_bps: u16,
+            floor_bps: u16,
+            decay_start: BlockNumber,
+            decay_end: BlockNumber,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if fee schedule is valid
+            if start_bps > 10_000 || floor_bps > start_bps || decay_end < decay_start {
+                return Err(Error::InvalidFee);
+            }
+
+            self.fee_start_bps = start_bps;
+            self.fee_floor_bps = floor_bps;
+            self.fee_decay_start = decay_start;
+            self.fee_decay_end = decay_end;
+
+            Ok(())
+        }
+
+        /// Sets a flat transfer fee of `bps` basis points, replacing any fee decay.
+        /// Note: only the manager can set the transfer fee.
+        /// Note: rates above 10000 bps are rejected with InvalidFee.
+        #[ink(message)]
+        pub fn set_transfer_fee(&mut self, bps: u16) -> Result<(), Error> {
+            // a flat fee is a decay from and to the same rate
+            self.set_fee_decay(bps, bps, 0, 0)
+        }
+
+        /// Returns the contract notified of every `transfer`, `confirm_large_transfer`
+        /// and `transfer_from`, if any.
+        #[ink(message)]
+        pub fn transfer_hook(&self) -> Option<AccountId> {
+            self.transfer_hook
+        }
+
+        /// Returns whether a failing transfer hook reverts the transfer.
+        #[ink(message)]
+        pub fn hook_must_succeed(&self) -> bool {
+            self.hook_must_succeed
+        }
+
+        /// Sets the contract notified of transfers through `TransferHook::on_transfer`.
+        /// Note: only the manager can set the transfer hook, `None` removes it.
+        /// Note: with `must_succeed`, a hook that fails or rejects the transfer reverts it,
+        /// otherwise hook failures are ignored.
+        #[ink(message)]
+        pub fn set_transfer_hook(
+            &mut self,
+            hook: Option<AccountId>,
+            must_succeed: bool,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.transfer_hook = hook;
+            self.hook_must_succeed = must_succeed;
+
+            Ok(())
+        }
+
+        /// Returns the minimum number of milliseconds between two transfers of an account.
+        #[ink(message)]
+        pub fn transfer_cooldown(&self) -> Timestamp {
+            self.transfer_cooldown
+        }
+
+        /// Sets the minimum number of milliseconds between two transfers of an account,
+        /// e.g. to deter wash trading. A cooldown of 0 disables it.
+        /// Note: only the manager can set the transfer cooldown.
+        /// Note: freeze exempt accounts bypass the cooldown.
+        #[ink(message)]
+        pub fn set_transfer_cooldown(&mut self, cooldown: Timestamp) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.transfer_cooldown = cooldown;
+
+            Ok(())
+        }
+
+        /// Returns whether the asset is a pure registry.
+        /// Note: a registry keeps opt-ins, freezes and roles, but its tokens never move.
+        #[ink(message)]
+        pub fn registry_mode(&self) -> bool {
+            self.registry_mode
+        }
+
+        /// Returns whether the asset is paused.
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Returns whether the supply is locked against minting and burning.
+        #[ink(message)]
+        pub fn is_supply_locked(&self) -> bool {
+            self.supply_locked
+        }
+
+        /// Locks the supply for good, so `mint` and `burn` always fail afterwards.
+        /// Note: only the manager can lock the supply.
+        /// Note: there is no way to unlock the supply.
+        #[ink(message)]
+        pub fn lock_supply(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if supply is already locked
+            if self.supply_locked {
+                return Err(Error::SupplyLocked);
+            }
+
+            self.supply_locked = true;
+
+            // emit supply locked event
+            self.emit(
+                self.manager_id,
+                SupplyLocked {
+                    asset_id: self.asset_id(),
+                    manager_id: self.manager_id,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::LockSupply, self.asset_id());
+
+            Ok(())
+        }
+
+        /// Pauses all transfers of the asset, as well as opt-ins and clawbacks.
+        /// Note: only the manager can pause an asset.
+        /// Note: the emergency exit stays available while paused, if enabled.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.paused = true;
+
+            // emit paused event
+            self.emit(
+                self.manager_id,
+                Paused {
+                    asset_id: self.asset_id(),
+                    manager_id: self.manager_id,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Pause, self.asset_id());
+
+            Ok(())
+        }
+
+        /// Resumes transfers of the asset.
+        /// Note: only the manager can unpause an asset.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.paused = false;
+
+            // emit unpaused event
+            self.emit(
+                self.manager_id,
+                Unpaused {
+                    asset_id: self.asset_id(),
+                    manager_id: self.manager_id,
+                },
+            );
+
+            // record privileged action
+            self.log_action(AuditAction::Unpause, self.asset_id());
+
+            Ok(())
+        }
+
+        /// Returns whether holders can use the emergency exit.
+        #[ink(message)]
+        pub fn emergency_exit_enabled(&self) -> bool {
+            self.emergency_exit_enabled
+        }
+
+        /// Enables or disables the emergency exit.
+        /// Note: only the manager can enable the emergency exit.
+        #[ink(message)]
+        pub fn set_emergency_exit(&mut self, enabled: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.emergency_exit_enabled = enabled;
+
+            Ok(())
+        }
+
+        /// Moves the caller's full balance to `to`, even while the asset is paused.
+        /// Note: the emergency exit must be enabled by the manager.
+        /// Note: frozen accounts can neither exit nor receive an exit, unless freeze exempt.
+        #[ink(message)]
+        pub fn emergency_exit(&mut self, to: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if emergency exit is enabled
+            if !self.emergency_exit_enabled {
+                return Err(Error::EmergencyExitDisabled);
+            }
+
+            // check if caller or receiver is frozen
+            if self.is_frozen_for_transfer(caller) || self.is_frozen_for_transfer(to) {
+                return Err(Error::FrozenAccount);
+            }
+
+            // check if receiver has opted in
+            let receiver_opted_in = self.accounts_opted_in.get(to).unwrap_or(false);
+            if !receiver_opted_in {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if caller has anything to exit
+            let amount = self.balances.get(caller).unwrap_or(0);
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // check if caller has locked balance
+            if self.locked_balance(caller) > 0 {
+                return Err(Error::BalanceLocked);
+            }
+
+            // check if reserve keeps its minimum balance
+            if caller != to {
+                self.ensure_min_reserve(caller, 0)?;
+            }
+
+            // update caller and receiver balances
+            self.move_balance(caller, to, amount)?;
+
+            // emit emergency exit event
+            self.emit(
+                caller,
+                EmergencyExit {
+                    asset_id: self.asset_id(),
+                    account: caller,
+                    to,
+                    amount,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Records the current balances as a new snapshot and returns its ID.
+        /// Note: only the manager can take a snapshot.
+        /// Note: balances are copied lazily, right before the first change after the snapshot,
+        /// while the issued supply is recorded right away.
+        #[ink(message)]
+        pub fn take_snapshot(&mut self) -> Result<u32, Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            self.record_snapshot(caller)
+        }
+
+        /// Deposits the native currency sent along as a dividend, takes a snapshot for it
+        /// and returns the dividend's ID.
+        /// Note: only the manager can deposit a dividend.
+        /// Note: every account holding tokens at the snapshot, the reserve included, can claim
+        /// its share with `claim_dividend`.
+        #[ink(message, payable)]
+        pub fn deposit_dividend(&mut self) -> Result<u32, Error> {
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if there is anything to distribute, and anyone to distribute it to
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if self.issued == 0 {
+                return Err(Error::NoHolders);
+            }
+
+            let dividend_id = self.dividend_count.checked_add(1).ok_or(Error::Overflow)?;
+            let snapshot_id = self.record_snapshot(caller)?;
+            self.dividend_count = dividend_id;
+            self.dividends.insert(dividend_id, &(snapshot_id, amount));
+
+            // emit dividend deposited event
+            self.emit(
+                caller,
+                DividendDeposited {
+                    asset_id: self.asset_id(),
+                    dividend_id,
+                    snapshot_id,
+                    amount,
+                },
+            );
+
+            Ok(dividend_id)
+        }
+
+        /// Returns the `(snapshot id, amount)` of dividend `dividend_id`.
+        #[ink(message)]
+        pub fn dividend(&self, dividend_id: u32) -> Option<(u32, Balance)> {
+            self.dividends.get(dividend_id)
+        }
+
+        /// Returns the share of dividend `dividend_id` owed to `holder`, pro rata to its balance
+        /// at the dividend's snapshot.
+        /// Note: the rounding remainders of the shares stay with the contract.
+        #[ink(message)]
+        pub fn dividend_of(&self, dividend_id: u32, holder: AccountId) -> Result<Balance, Error> {
+            let (snapshot_id, amount) = self
+                .dividends
+                .get(dividend_id)
+                .ok_or(Error::NoSuchDividend)?;
+            let balance = self.balance_of_at(holder, snapshot_id)?;
+            let supply = self.total_supply_at(snapshot_id)?;
+
+            amount
+                .checked_mul(balance)
+                .map(|weighted| weighted / supply)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Returns whether `holder` has claimed its share of dividend `dividend_id`.
+        #[ink(message)]
+        pub fn dividend_claimed(&self, dividend_id: u32, holder: AccountId) -> bool {
+            self.dividend_claims
+                .get((dividend_id, holder))
+                .unwrap_or(false)
+        }
+
+        /// Pays the caller its share of dividend `dividend_id` in native currency and returns it.
+        /// Note: the share is paid to the payout address of the caller.
+        /// Note: each holder can claim a dividend once, AlreadyClaimed is returned otherwise.
+        /// Note: ZeroAmount is returned if the caller held no tokens at the snapshot.
+        #[ink(message)]
+        pub fn claim_dividend(&mut self, dividend_id: u32) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+
+            // check if the share has already been claimed
+            if self.dividend_claimed(dividend_id, caller) {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            // check if there is a share to claim
+            let share = self.dividend_of(dividend_id, caller)?;
+            if share == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            // mark the share as claimed before paying it out
+            self.dividend_claims.insert((dividend_id, caller), &true);
+            if self
+                .env()
+                .transfer(self.payout_address_of(caller), share)
+                .is_err()
+            {
+                self.dividend_claims.remove((dividend_id, caller));
+                return Err(Error::NativeTransferFailed);
+            }
+
+            // emit dividend claimed event
+            self.emit(
+                caller,
+                DividendClaimed {
+                    asset_id: self.asset_id(),
+                    holder: caller,
+                    dividend_id,
+                    amount: share,
+                },
+            );
+
+            Ok(share)
+        }
+
+        /// Records the current balances as a new snapshot on behalf of `caller` and returns its ID.
+        fn record_snapshot(&mut self, caller: AccountId) -> Result<u32, Error> {
+            let snapshot_id = self.snapshot_count.checked_add(1).ok_or(Error::Overflow)?;
+            let block_number = self.env().block_number();
+            self.snapshot_count = snapshot_id;
+            self.snapshot_blocks.insert(snapshot_id, &block_number);
+            self.snapshot_supplies.insert(snapshot_id, &self.issued);
+
+            // emit snapshot event
+            self.emit(
+                caller,
+                Snapshot {
+                    asset_id: self.asset_id(),
+                    snapshot_id,
+                    block_number,
+                },
+            );
+
+            Ok(snapshot_id)
+        }
+
+        /// Returns the block number at which snapshot `snapshot_id` was taken.
+        #[ink(message)]
+        pub fn snapshot_block(&self, snapshot_id: u32) -> Result<BlockNumber, Error> {
+            self.snapshot_blocks
+                .get(snapshot_id)
+                .ok_or(Error::InvalidSnapshot)
+        }
+
+        /// Returns the issued supply at snapshot `snapshot_id`, e.g. to compute pro rata shares.
+        #[ink(message)]
+        pub fn total_supply_at(&self, snapshot_id: u32) -> Result<Balance, Error> {
+            self.snapshot_supplies
+                .get(snapshot_id)
+                .ok_or(Error::InvalidSnapshot)
+        }
+
+        /// Returns the balance of `owner` at snapshot `snapshot_id`.
+        /// Note: if the snapshot doesn't exist, InvalidSnapshot is returned.
+        #[ink(message)]
+        pub fn balance_of_at(&self, owner: AccountId, snapshot_id: u32) -> Result<Balance, Error> {
+            if snapshot_id == 0 || snapshot_id > self.snapshot_count {
+                return Err(Error::InvalidSnapshot);
+            }
+
+            // the first checkpoint at or after the snapshot holds the balance at the snapshot,
+            // binary searched so the lookup stays cheap however many snapshots were taken
+            let mut low = 0;
+            let mut high = self.checkpoint_counts.get(owner).unwrap_or(0);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                let (id, _) = self
+                    .balance_checkpoints
+                    .get((owner, mid))
+                    .unwrap_or_default();
+                if id < snapshot_id {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+
+            // if there is none the balance hasn't changed since
+            match self.balance_checkpoints.get((owner, low)) {
+                Some((_, balance)) => Ok(balance),
+                None => Ok(self.balances.get(owner).unwrap_or(0)),
+            }
+        }
+
+        // Destroy an asset
+        // Note: only the manager can destroy an asset
+        // Note: all holdings must have been returned to the manager or the reserve
+        // Note: with an approval threshold above 1, destruction goes through `propose_action`
+        #[ink(message)]
+        pub fn destroy_asset(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            // check if caller is the manager
+            if !self.is_authorized(caller, RoleKind::Manager) {
+                return Err(Error::NotManagerId);
+            }
+
+            // check if the action must be proposed, for co-managers to approve
+            // or the timelock to pass
+            if self.requires_proposal() {
+                return Err(Error::ApprovalRequired);
+            }
+
+            self.apply_destroy()
+        }
+
+        /// Destroys the asset and terminates the contract, which clears its storage and
+        /// returns the storage deposit to the manager.
+        fn apply_destroy(&mut self) -> Result<(), Error> {
+            // check if manager and reserve hold every token, including escrowed and
+            // assigned ones
+            if !self.is_destroyable() {
+                return Err(Error::NotAllAssetsOwnedByManager);
+            }
+
+            // emit destroy asset event
+            self.emit(
+                self.manager_id,
+                Destruction {
+                    asset_id: self.asset_id(),
+                    destroyer: self.manager_id,
+                },
+            );
+
+            // terminate contract
+            self.env().terminate_contract(self.manager_id);
+        }
+
+        /// Sets the allowance of `spender` over the tokens of `owner`, records the change
+        /// in the allowance history and emits an `Approval` event.
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, amount: Balance) {
+            self.allowances.insert((owner, spender), &amount);
+
+            // record approval change
+            let len = self
+                .allowance_history_len
+                .get((owner, spender))
+                .unwrap_or(0);
+            self.allowance_history.insert(
+                (owner, spender, len % ALLOWANCE_HISTORY_LIMIT),
+                &(self.env().block_number(), amount),
+            );
+            self.allowance_history_len
+                .insert((owner, spender), &(len + 1));
+
+            // emit approval event
+            self.emit(
+                owner,
+                Approval {
+                    asset_id: self.asset_id(),
+                    owner,
+                    spender,
+                    amount,
+                },
+            );
+        }
+
+        /// Returns whether `caller` can act as `role`.
+        /// Note: the controller, if any, can act as every role that hasn't been cleared.
+        fn is_authorized(&self, caller: AccountId, role: RoleKind) -> bool {
+            !self.role_cleared(role)
+                && (self.has_role(role, caller) || self.controller == Some(caller))
+        }
+
+        /// Returns whether the authority address of `role` has been cleared for good.
+        fn role_cleared(&self, role: RoleKind) -> bool {
+            match role {
+                RoleKind::Manager => self.management_renounced,
+                RoleKind::Reserve => self.reserve_renounced,
+                RoleKind::Freeze => self.freeze_renounced,
+                RoleKind::Clawback => self.clawback_renounced,
+                RoleKind::Custom(_) => false,
+            }
+        }
+
+        /// Returns whether `account` is the manager or a co-manager.
+        fn is_signer(&self, account: AccountId) -> bool {
+            account == self.manager_id || self.co_managers.contains(&account)
+        }
+
+        /// Checks that `account` can propose, approve and execute `action`.
+        /// Note: clawbacks and freezes of the reserve are signed by the clawback and freeze
+        /// authorities instead of the managers, so the manager can't use a proposal to act
+        /// in their place.
+        fn ensure_can_sign(&self, account: AccountId, action: &PendingAction) -> Result<(), Error> {
+            match action {
+                PendingAction::Revoke { .. } | PendingAction::ForceTransfer { .. } => {
+                    // check if clawback has been renounced
+                    if self.clawback_renounced {
+                        return Err(Error::ClawbackRenounced);
+                    }
+
+                    // check if asset has a clawback address and caller holds the role
+                    if self.clawback_id == AccountId::from([0x0; 32])
+                        || !self.is_authorized(account, RoleKind::Clawback)
+                    {
+                        return Err(Error::NotClawbackId);
+                    }
+                }
+                PendingAction::FreezeReserve { .. } => {
+                    // check if token can be frozen
+                    if !self.freezable {
+                        return Err(Error::NotFreezable);
+                    }
+
+                    // check if freezing has been renounced
+                    if self.freeze_renounced {
+                        return Err(Error::FreezeRenounced);
+                    }
+
+                    // check if caller is the freeze address
+                    if !self.is_authorized(account, RoleKind::Freeze) {
+                        return Err(Error::NotFreezeId);
+                    }
+                }
+                _ => {
+                    // check if caller is the manager or a co-manager
+                    if !self.is_signer(account) {
+                        return Err(Error::NotManagerId);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Emits a `Modify` event carrying the current authorities.
+        fn emit_modify(&self) {
+            self.emit(
+                self.manager_id,
+                Modify {
+                    manager_id: self.manager_id,
+                    reserve_id: self.reserve_id,
+                    freeze_id: self.freeze_id,
+                    clawback_id: self.clawback_id,
+                },
+            );
+        }
+
+        /// Emits `event`, or a `Compact` event carrying it if the asset uses the compact event schema.
+        /// Note: `account` is the primary account of the event, indexed by the `Compact` event.
+        fn emit<E>(&self, account: AccountId, event: E)
+        where
+            E: Into<Event>,
+        {
+            if self.compact_events {
+                self.env().emit_event(Compact {
+                    asset_id: self.asset_id(),
+                    account,
+                    data: event.into().encode(),
+                });
+            } else {
+                self.env().emit_event(event.into());
+            }
+        }
+
+        /// Sets the balance of `account`, records the current block as its last activity and
+        /// its inflow or outflow, and updates the top holder.
+        /// Note: the previous balance is checkpointed for the latest snapshot first if that
+        /// hasn't happened yet.
+        fn set_balance(&mut self, account: AccountId, balance: Balance) {
+            let checkpoints = self.checkpoint_counts.get(account).unwrap_or(0);
+            let last_snapshot = checkpoints
+                .checked_sub(1)
+                .and_then(|last| self.balance_checkpoints.get((account, last)))
+                .map_or(0, |(id, _)| id);
+            if last_snapshot < self.snapshot_count {
+                self.balance_checkpoints.insert(
+                    (account, checkpoints),
+                    &(self.snapshot_count, self.balances.get(account).unwrap_or(0)),
+                );
+                self.checkpoint_counts.insert(account, &(checkpoints + 1));
+            }
+
+            // keep the frozen balance counter in sync
+            if self.is_frozen(account) {
+                self.total_frozen =
+                    self.total_frozen - self.balances.get(account).unwrap_or(0) + balance;
+            }
+
+            self.release_expired_lock(account);
+
+            // accumulate lifetime inflows and outflows
+            let previous = self.balances.get(account).unwrap_or(0);
+            let (total_in, total_out) = self.net_flows.get(account).unwrap_or((0, 0));
+            let flows = if balance >= previous {
+                (total_in.saturating_add(balance - previous), total_out)
+            } else {
+                (total_in, total_out.saturating_add(previous - balance))
+            };
+            self.net_flows.insert(account, &flows);
+
+            // keep the running sum of balances in sync
+            self.sum_balances = self.sum_balances + balance - previous;
+
+            // keep the number of accounts holding tokens in sync
+            if previous == 0 && balance > 0 {
+                self.holder_count += 1;
+            } else if previous > 0 && balance == 0 {
+                self.holder_count -= 1;
+            }
+
+            self.balances.insert(account, &balance);
+            self.last_activity
+                .insert(account, &self.env().block_number());
+
+            // take over as top holder when exceeding the current one, or look for the
+            // largest holder when the top holder's balance drops
+            if account == self.top_holder && balance < previous {
+                self.top_holder = self.find_top_holder();
+            } else if balance > self.balances.get(self.top_holder).unwrap_or(0) {
+                self.top_holder = account;
+            }
+        }
+
+        /// Returns the opted in account with the largest balance, the current top holder on
+        /// a tie.
+        fn find_top_holder(&self) -> AccountId {
+            let mut top_holder = self.top_holder;
+            let mut top_balance = self.balances.get(top_holder).unwrap_or(0);
+            for position in 0..self.opted_in_count {
+                if let Some(holder) = self.holder_ids.get(position) {
+                    let balance = self.balances.get(holder).unwrap_or(0);
+                    if balance > top_balance {
+                        top_holder = holder;
+                        top_balance = balance;
+                    }
+                }
+            }
+
+            top_holder
+        }
+
+        /// Sets the frozen status of `account` and keeps the frozen balance counter in sync.
+        fn set_frozen(&mut self, account: AccountId, frozen: bool) {
+            let balance = self.balances.get(account).unwrap_or(0);
+            match (self.is_frozen(account), frozen) {
+                (false, true) => self.total_frozen += balance,
+                (true, false) => self.total_frozen -= balance,
+                _ => {}
+            }
+
+            self.frozen_holders.insert(account, &frozen);
+        }
+
+        /// Removes the explicit frozen status of `account`, so it follows its default again,
+        /// and keeps the frozen balance counter in sync.
+        fn clear_frozen(&mut self, account: AccountId) {
+            let balance = self.balances.get(account).unwrap_or(0);
+            let was_frozen = self.is_frozen(account);
+            self.frozen_holders.remove(account);
+            match (was_frozen, self.is_frozen(account)) {
+                (false, true) => self.total_frozen += balance,
+                (true, false) => self.total_frozen -= balance,
+                _ => {}
+            }
+        }
+
+        /// Returns the default frozen status of `account`, its override if any or `default_frozen`.
+        fn default_frozen_of(&self, account: AccountId) -> bool {
+            self.freeze_default_override
+                .get(account)
+                .unwrap_or(self.default_frozen)
+        }
+
+        /// Removes the lock of `account` from the locked balance counter once it has expired.
+        fn release_expired_lock(&mut self, account: AccountId) {
+            if let Some((amount, release_ts)) = self.locked.get(account) {
+                if self.env().block_timestamp() >= release_ts {
+                    self.total_locked -= amount;
+                    self.locked.remove(account);
+                }
+            }
+        }
+
+        /// Appends `account` to the index of opted in accounts.
+        fn index_holder(&mut self, account: AccountId) {
+            self.holder_ids.insert(self.opted_in_count, &account);
+            self.holder_positions.insert(account, &self.opted_in_count);
+            self.opted_in_count += 1;
+        }
+
+        /// Removes `account` from the index of opted in accounts, moving the last indexed
+        /// account into its position.
+        fn unindex_holder(&mut self, account: AccountId) {
+            // check if account is indexed
+            let position = match self.holder_positions.get(account) {
+                Some(position) => position,
+                None => return,
+            };
+
+            let last = self.opted_in_count - 1;
+            if position != last {
+                if let Some(moved) = self.holder_ids.get(last) {
+                    self.holder_ids.insert(position, &moved);
+                    self.holder_positions.insert(moved, &position);
+                }
+            }
+            self.holder_positions.remove(account);
+            self.holder_ids.remove(last);
+            self.opted_in_count = last;
+        }
+
+        /// Opts `account` out of this asset and emits an `OptOut` event.
+        fn opt_out_account(&mut self, account: AccountId) {
+            // update account's opt in status
+            self.accounts_opted_in.insert(account, &false);
+            self.unindex_holder(account);
+            self.opt_in_ts.remove(account);
+
+            // emit opt out event
+            self.emit(
+                account,
+                OptOut {
+                    asset_id: self.asset_id(),
+                    account,
+                },
+            );
+        }
+
+        /// Opts `account` in to this asset and emits an `OptIn` event.
+        fn opt_in_account(&mut self, account: AccountId) -> Result<(), Error> {
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if account has already opted in
+            let account_opted_in = self.accounts_opted_in.get(account).unwrap_or(false);
+            if account_opted_in {
+                return Err(Error::AlreadyOptedIn);
+            }
+
+            // check if the holder cap is reached
+            if let Some(max_holders) = self.max_holders {
+                if self.opted_in_count >= max_holders {
+                    return Err(Error::MaxHoldersReached);
+                }
+            }
+
+            // update account's opt in status
+            self.accounts_opted_in.insert(account, &true);
+            self.index_holder(account);
+            self.opt_in_ts
+                .insert(account, &self.env().block_timestamp());
+
+            // emit opt in event
+            let frozen = self.default_frozen_of(account);
+            self.emit(
+                account,
+                OptIn {
+                    asset_id: self.asset_id(),
+                    account,
+                    frozen,
+                },
+            );
+
+            // new holders of a default frozen asset start frozen
+            // until the freeze address unfreezes them
+            if self.default_frozen && frozen {
+                let previous = self.frozen_holders.get(account).unwrap_or(false);
+
+                // drop an unfreeze left from an earlier opt-in, the default applies without
+                // an explicit status, so an override set later still takes effect
+                if self.frozen_holders.get(account) == Some(false) {
+                    self.clear_frozen(account);
+                }
+
+                // emit freeze event
+                self.emit(
+                    account,
+                    Freeze {
+                        asset_id: self.asset_id(),
+                        account,
+                        freeze: true,
+                        freeze_id: self.freeze_id,
+                        previous,
+                        reason: 0,
+                    },
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Appends a privileged `action` of the caller on `target` to the audit log.
+        fn log_action(&mut self, action: AuditAction, target: AccountId) {
+            let entry = AuditEntry {
+                actor: self.env().caller(),
+                action,
+                target,
+                block: self.env().block_number(),
+            };
+            self.audit_entries
+                .insert(self.audit_count % AUDIT_LOG_LIMIT, &entry);
+            self.audit_count += 1;
+        }
+
+        /// Returns ReserveBelowMinimum if `account` is the reserve and `remaining` is below the minimum reserve.
+        fn ensure_min_reserve(&self, account: AccountId, remaining: Balance) -> Result<(), Error> {
+            if account == self.reserve_id && remaining < self.min_reserve {
+                return Err(Error::ReserveBelowMinimum);
+            }
+
+            Ok(())
+        }
+
+        /// Calls the transfer hook, if any, about a completed transfer.
+        /// Note: a mandatory hook that fails or rejects the transfer traps the call,
+        /// which reverts the whole transfer.
+        fn notify_transfer_hook(&self, from: AccountId, to: AccountId, amount: Balance) {
+            if let Some(hook) = self.transfer_hook {
+                let accepted = Self::call_transfer_hook(hook, from, to, amount);
+                if !accepted && self.hook_must_succeed {
+                    panic!("transfer hook failed");
+                }
+            }
+        }
+
+        /// Calls `TransferHook::on_transfer` on `hook` and returns whether it accepted.
+        #[cfg(not(test))]
+        fn call_transfer_hook(
+            hook: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> bool {
+            use ink_env::call::{build_call, Call, ExecutionInput, Selector};
+
+            let result = build_call::<Environment>()
+                .call_type(Call::new().callee(hook))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                        "TransferHook::on_transfer"
+                    )))
+                    .push_arg(from)
+                    .push_arg(to)
+                    .push_arg(amount),
+                )
+                .returns::<bool>()
+                .fire();
+
+            matches!(result, Ok(true))
+        }
+
+        /// Forwards the hook call to the mock hook of the unit tests.
+        #[cfg(test)]
+        fn call_transfer_hook(
+            hook: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> bool {
+            tests::mock_transfer_hook(hook, from, to, amount)
+        }
+
+        /// Returns BelowMinimumBalance if `balance` is nonzero but below the minimum balance.
+        /// Note: a balance of exactly zero is always allowed.
+        fn ensure_min_balance(&self, balance: Balance) -> Result<(), Error> {
+            if balance != 0 && balance < self.min_balance {
+                return Err(Error::BelowMinimumBalance);
+            }
+
+            Ok(())
+        }
+
+        /// Returns the fee `sender` pays to the reserve on a transfer of `amount` to `receiver`
+        /// at a rate of `fee_bps`.
+        /// Note: transfers to self and from the reserve are free.
+        fn transfer_fee(
+            &self,
+            sender: AccountId,
+            receiver: AccountId,
+            amount: Balance,
+            fee_bps: u16,
+        ) -> Balance {
+            if sender == receiver || sender == self.reserve_id {
+                return 0;
+            }
+
+            amount * fee_bps as Balance / 10_000
+        }
+
+        /// Returns the sum of `amounts`, or Overflow if it doesn't fit in a `Balance`.
+        fn checked_sum(mut amounts: impl Iterator<Item = Balance>) -> Result<Balance, Error> {
+            amounts.try_fold(0, |sum: Balance, amount| {
+                sum.checked_add(amount).ok_or(Error::Overflow)
+            })
+        }
+
+        /// Returns DuplicateRecipient if an account is listed more than once.
+        fn ensure_unique_recipients(&self, recipients: &[AccountId]) -> Result<(), Error> {
+            for (index, recipient) in recipients.iter().enumerate() {
+                if recipients[..index].contains(recipient) {
+                    return Err(Error::DuplicateRecipient);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Returns an error if `account` can't receive tokens from a batch operation.
+        fn ensure_can_receive(&self, account: AccountId) -> Result<(), Error> {
+            // check if account has opted in
+            if !self.accounts_opted_in.get(account).unwrap_or(false) {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if account is frozen
+            if self.is_frozen_for_transfer(account) {
+                return Err(Error::FrozenAccount);
+            }
+
+            Ok(())
+        }
+
+        /// Returns the account escrowed and assigned tokens are attributed to in `Transfer`
+        /// events, i.e. the address of the contract.
+        fn escrow_account(&self) -> AccountId {
+            self.env().account_id()
+        }
+
+        /// Returns whether `account` is frozen and not exempt from freezes.
+        fn is_frozen_for_transfer(&self, account: AccountId) -> bool {
+            self.is_frozen(account) && !self.is_freeze_exempt(account)
+        }
+
+        /// Adds `amount` to the lifetime received total of `account`.
+        /// Note: called before any other write, so an overflow leaves the state unchanged.
+        fn record_received(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            let received = self
+                .received_of(account)
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            self.received_totals.insert(account, &received);
+
+            Ok(())
+        }
+
+        /// Moves `amount` of tokens from `from` to `to`.
+        /// Note: both balances are read right before they are written, so a batch where an
+        /// account receives and then sends always works on its current balance.
+        /// Note: a move to self leaves the balance unchanged.
+        fn move_balance(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            // check if from account has enough balance
+            let from_remaining = self
+                .balances
+                .get(from)
+                .unwrap_or(0)
+                .checked_sub(amount)
+                .ok_or(Error::NotEnoughBalance)?;
+
+            if from == to {
+                return Ok(());
+            }
+
+            // check if to account balance can hold the amount
+            let to_balance = self
+                .balances
+                .get(to)
+                .unwrap_or(0)
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+
+            self.record_received(to, amount)?;
+            self.set_balance(from, from_remaining);
+            self.set_balance(to, to_balance);
+
+            Ok(())
+        }
+
+        /// Returns ConfirmationRequired if `amount` is above the large transfer threshold.
+        fn ensure_no_confirmation_required(&self, amount: Balance) -> Result<(), Error> {
+            if let Some(threshold) = self.large_transfer_threshold {
+                if amount > threshold {
+                    return Err(Error::ConfirmationRequired);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Returns CooldownActive if `sender` sent tokens less than the transfer cooldown ago.
+        /// Note: freeze exempt accounts bypass the cooldown.
+        fn ensure_cooldown_passed(&self, sender: AccountId) -> Result<(), Error> {
+            if self.transfer_cooldown == 0 || self.is_freeze_exempt(sender) {
+                return Ok(());
+            }
+
+            if let Some(last_transfer_ts) = self.last_transfer_ts.get(sender) {
+                let elapsed = self
+                    .env()
+                    .block_timestamp()
+                    .saturating_sub(last_transfer_ts);
+                if elapsed < self.transfer_cooldown {
+                    return Err(Error::CooldownActive);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Records the current time as the last send of `sender`, starting its transfer cooldown.
+        fn record_send(&mut self, sender: AccountId, authorization: Authorization) {
+            if authorization != Authorization::Authority {
+                self.last_transfer_ts
+                    .insert(sender, &self.env().block_timestamp());
+            }
+        }
+
+        /// Checks that `amount` of tokens can leave `sender`, and returns its remaining balance.
+        /// Note: shared by every path moving a holder's tokens, so none of them skips a check.
+        /// Note: the minimum balance checks are left to the caller, as they don't apply to a
+        /// transfer to self.
+        fn validate_send(
+            &self,
+            sender: AccountId,
+            amount: Balance,
+            authorization: Authorization,
+        ) -> Result<Balance, Error> {
+            // check if asset is a pure registry
+            if self.registry_mode {
+                return Err(Error::RegistryMode);
+            }
+
+            // check if asset is paused
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+
+            // check if amount requires a two-step confirmation
+            if authorization == Authorization::Holder {
+                self.ensure_no_confirmation_required(amount)?;
+            }
+
+            // check if sender's transfer cooldown has passed
+            if authorization != Authorization::Authority {
+                self.ensure_cooldown_passed(sender)?;
+            }
+
+            // check if sender has enough balance
+            let sender_remaining = self
+                .balances
+                .get(sender)
+                .unwrap_or(0)
+                .checked_sub(amount)
+                .ok_or(Error::NotEnoughBalance)?;
+
+            // check if transfer dips into locked balance
+            if sender_remaining < self.locked_balance(sender) {
+                return Err(Error::BalanceLocked);
+            }
+
+            // check if sender is frozen
+            if self.is_frozen_for_transfer(sender) {
+                return Err(Error::FrozenAccount);
+            }
+
+            Ok(sender_remaining)
+        }
+
+        /// Checks that `amount` of tokens can move from `sender` to `receiver`, and returns
+        /// the remaining balance of `sender`, whether `receiver` has opted in, and the fee.
+        /// Note: shared by `transfer_from_to` and `can_transfer`, so they never drift apart.
+        fn validate_transfer(
+            &self,
+            sender: AccountId,
+            receiver: AccountId,
+            amount: Balance,
+            authorization: Authorization,
+        ) -> Result<(Balance, bool, Balance), Error> {
+            // check if receiver is the zero address, reserved for mint and burn events
+            if receiver == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+
+            let sender_remaining = self.validate_send(sender, amount, authorization)?;
+
+            // check if receiver has opted in, or the transfer can be escrowed instead
+            let receiver_opted_in = self.accounts_opted_in.get(receiver).unwrap_or(false);
+            if !receiver_opted_in && !self.allow_escrow {
+                return Err(Error::NotOptedIn);
+            }
+
+            // check if receiver is frozen, escrowed tokens are held for the receiver
+            // until it opts in
+            if receiver_opted_in && self.is_frozen_for_transfer(receiver) {
+                return Err(Error::FrozenAccount);
+            }
+
+            // compute transfer fee, transfers to self and from the reserve are free
+            let fee = self.transfer_fee(sender, receiver, amount, self.current_fee_bps());
+
+            // check if reserve can receive the fee
+            if fee > 0 && !self.accounts_opted_in.get(self.reserve_id).unwrap_or(false) {
+                return Err(Error::NotOptedIn);
+            }
+
+            // dust and reserve checks don't apply to a transfer to self
+            if sender != receiver {
+                // check if reserve keeps its minimum balance
+                self.ensure_min_reserve(sender, sender_remaining)?;
+
+                // check if neither side is left with a dust balance
+                let receiver_held = if receiver_opted_in {
+                    self.balances.get(receiver).unwrap_or(0)
+                } else {
+                    self.pending.get(receiver).unwrap_or(0)
+                };
+                self.ensure_min_balance(sender_remaining)?;
+                self.ensure_min_balance(receiver_held + amount - fee)?;
+            }
+
+            Ok((sender_remaining, receiver_opted_in, fee))
+        }
+
+        /// Moves `amount` of tokens from `sender` to `receiver` and emits a `Transfer` event.
+        /// Note: the current transfer fee is deducted from `amount` and credited to the reserve.
+        /// Note: when escrow is allowed, tokens sent to a receiver that hasn't opted in are held
+        /// as its pending balance.
+        fn transfer_from_to(
+            &mut self,
+            sender: AccountId,
+            receiver: AccountId,
+            amount: Balance,
+            authorization: Authorization,
+        ) -> Result<(), Error> {
+            self.transfer_noted(sender, receiver, amount, Vec::new(), authorization)
+        }
+
+        /// Moves `amount` of tokens from `sender` to `receiver` and emits a `Transfer` event
+        /// carrying `note`.
+        /// Note: the fee transfer to the reserve, if any, carries no note.
+        fn transfer_noted(
+            &mut self,
+            sender: AccountId,
+            receiver: AccountId,
+            amount: Balance,
+            note: Vec<u8>,
+            authorization: Authorization,
+        ) -> Result<(), Error> {
+            let (sender_remaining, receiver_opted_in, fee) =
+                self.validate_transfer(sender, receiver, amount, authorization)?;
+            self.record_send(sender, authorization);
+
+            // a transfer to self leaves the balance unchanged
+            if sender != receiver {
+                if receiver_opted_in {
+                    self.move_balance(sender, receiver, amount - fee)?;
+                } else {
+                    // hold the tokens until the receiver opts in and claims them
+                    self.set_balance(sender, sender_remaining + fee);
+                    let pending = self.pending.get(receiver).unwrap_or(0);
+                    if pending == 0 {
+                        self.pending_since
+                            .insert(receiver, &self.env().block_timestamp());
+                    }
+                    self.pending.insert(receiver, &(pending + amount - fee));
+                    self.sum_balances += amount - fee;
+                }
+                if fee > 0 {
+                    self.move_balance(sender, self.reserve_id, fee)?;
+                }
+            }
+
+            // emit transfer event, escrowed tokens are held by the escrow account
+            let escrowed = !receiver_opted_in && sender != receiver;
+            self.emit(
+                sender,
+                Transfer {
+                    sender,
+                    receiver: if escrowed {
+                        self.escrow_account()
+                    } else {
+                        receiver
+                    },
+                    asset_id: self.asset_id(),
+                    amount: amount - fee,
+                    note,
+                },
+            );
+
+            // emit fee transfer event
+            if fee > 0 {
+                self.emit(
+                    sender,
+                    Transfer {
+                        sender,
+                        receiver: self.reserve_id,
+                        asset_id: self.asset_id(),
+                        amount: fee,
+                        note: Vec::new(),
+                    },
+                );
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        /// Imports `ink_lang` so we can use `#[ink::test]`.
+        use ink_lang as ink;
+
+        thread_local! {
+            /// Calls received by the mock transfer hook, as `(hook, from, to, amount)`.
+            static HOOK_CALLS: core::cell::RefCell<Vec<(AccountId, AccountId, AccountId, Balance)>> =
+                const { core::cell::RefCell::new(Vec::new()) };
+            /// Whether the mock transfer hook accepts transfers.
+            static HOOK_ACCEPTS: core::cell::Cell<bool> = const { core::cell::Cell::new(true) };
+        }
+
+        /// Mock of a hook contract implementing `TransferHook`.
+        pub(super) fn mock_transfer_hook(
+            hook: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> bool {
+            HOOK_CALLS.with(|calls| calls.borrow_mut().push((hook, from, to, amount)));
+            HOOK_ACCEPTS.with(|accepts| accepts.get())
+        }
+
+        /// Returns the default test accounts.
+        fn default_accounts() -> ink_env::test::DefaultAccounts<ink_env::DefaultEnvironment> {
+            ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+        }
+
+        /// Sets the caller of the next message.
+        fn set_caller(caller: AccountId) {
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(caller);
+        }
+
+        /// Advances the chain by `n` blocks.
+        fn advance_blocks(n: u32) {
+            for _ in 0..n {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            }
+        }
+
+        /// Returns the parameters of an asset with alice as every authority and a supply
+        /// of 1000 tokens, for tests to override.
+        fn default_params() -> AssetParams {
+            let accounts = default_accounts();
+            AssetParams {
+                asset_id: None,
+                asset_name: "Test subsa".into(),
+                unit_name: "TSSA".into(),
+                total: 1000,
+                decimals: 10,
+                default_frozen: false,
+                url: "www.test.com".into(),
+                metadata_hash: [0x0; 4],
+                manager: Some(accounts.alice),
+                reserve: Some(accounts.alice),
+                freeze: Some(accounts.alice),
+                clawback: Some(accounts.alice),
+                compact_events: false,
+                max_holders: None,
+                registry_mode: false,
+                allow_escrow: false,
+                names_mutable: false,
+                co_managers: Vec::new(),
+                approval_threshold: 0,
+                min_balance: 0,
+                freezable: true,
+                max_supply: None,
+            }
+        }
+
+        /// Creates an asset with alice as the creator and every authority.
+        /// Note: alice holds the whole supply of 1000 tokens as the reserve.
+        fn create_asset() -> Subsa {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            Subsa::new(default_params())
+        }
+
+        /// Test constructor and initial state
+        #[ink::test]
+        fn constructor_works() {
+            let asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: Some(AccountId::from([0x0; 32])),
+                reserve: Some(AccountId::from([0x0; 32])),
+                freeze: Some(AccountId::from([0x0; 32])),
+                clawback: Some(AccountId::from([0x0; 32])),
+                ..default_params()
+            });
+
+            assert_eq!(asset.asset_name(), "Test subsa");
+            assert_eq!(asset.unit_name(), "TSSA");
+            assert_eq!(asset.total(), 1000);
+            assert_eq!(asset.decimals(), 10);
+            assert!(asset.default_frozen());
+            assert_eq!(asset.url(), "www.test.com");
+            assert_eq!(asset.manager_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.reserve_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.freeze_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.clawback_id(), AccountId::from([0x0; 32]));
+        }
+
+        // Test if constructor works with default values
+        #[ink::test]
+        fn constructor_works_with_default_values() {
+            let asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+
+            assert_eq!(asset.asset_name(), "Test subsa");
+            assert_eq!(asset.unit_name(), "TSSA");
+            assert_eq!(asset.total(), 1000);
+            assert_eq!(asset.decimals(), 10);
+            assert!(asset.default_frozen());
+            assert_eq!(asset.url(), "www.test.com");
+            assert_eq!(asset.manager_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.reserve_id(), AccountId::from([0x1; 32]));
+            assert_eq!(asset.freeze_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.clawback_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.balances.get(asset.reserve_id()).unwrap_or(0), 1000);
+        }
+
+        // Test if asset_id field is set correctly in constructor to the contract address
+        #[ink::test]
+        fn constructor_sets_asset_id() {
+            use ink_lang::codegen::Env;
+            let asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            assert_eq!(asset.asset_id(), asset.env().account_id());
+        }
+
+        // check if Create event is emitted in constructor
+        #[ink::test]
+        fn constructor_emits_create_event() {
+            let _asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 1);
+            let event = &events[0];
+            // event signature, asset id and creator, the name and total are data
+            assert_eq!(event.topics.len(), 3);
+            // TODO TEST EVENT WITH Event as scale:Decode
+        }
+
+        // Test if optIn works
+        #[ink::test]
+        fn opt_in_works() {
+            // set caller
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            asset.opt_in().unwrap();
+            // check if caller account is opted in in accounts_opted_in map
+            assert_eq!(
+                asset.accounts_opted_in.get(AccountId::from([0x1; 32])),
+                Some(true)
+            );
+        }
+
+        // Test if optIn emits OptIn event
+        #[ink::test]
+        fn opt_in_emits_opt_in_event() {
+            // set caller
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            asset.opt_in().unwrap();
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            // the asset is default frozen, so opting in also emits a Freeze event
+            assert_eq!(events.len(), 3);
+            let event = &events[1];
+            assert_eq!(event.topics.len(), 3);
+            // TODO TEST EVENT WITH Event as scale:Decode
+        }
+
+        // Test if optOut works
+        #[ink::test]
+        fn opt_out_works() {
+            // set caller
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            asset.opt_in().unwrap();
+            asset.opt_out().unwrap();
+            // check if caller account is opted in in accounts_opted_in map
+            assert_eq!(
+                asset.accounts_opted_in.get(AccountId::from([0x1; 32])),
+                Some(false)
+            );
+        }
+
+        // Test if optOut emits OptOut event
+        #[ink::test]
+        fn opt_out_emits_opt_out_event() {
+            // set caller
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                manager: None,
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            asset.opt_in().unwrap();
+            asset.opt_out().unwrap();
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            // the asset is default frozen, so opting in also emits a Freeze event
+            assert_eq!(events.len(), 4);
+            let event = &events[3];
+            assert_eq!(event.topics.len(), 3);
+            // TODO TEST EVENT WITH Event as scale:Decode
+        }
+
+        // Test if a transfer above the threshold requires a confirmation
+        #[ink::test]
+        fn large_transfer_requires_confirmation() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.transfer(accounts.bob, 500),
+                Err(Error::ConfirmationRequired)
+            );
+            asset.commit_large_transfer(accounts.bob, 500).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+            asset.confirm_large_transfer().unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(500));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(500));
+            assert_eq!(
+                asset.confirm_large_transfer(),
+                Err(Error::NoPendingTransfer)
+            );
+        }
+
+        // Test if a committed large transfer expires after the window
+        #[ink::test]
+        fn large_transfer_expires_without_confirmation() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            asset.commit_large_transfer(accounts.bob, 500).unwrap();
+            advance_blocks(3);
+            assert_eq!(asset.confirm_large_transfer(), Err(Error::TransferExpired));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+        }
+
+        // Test if a transfer below the threshold bypasses the confirmation
+        #[ink::test]
+        fn transfer_below_threshold_bypasses_confirmation() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if transfer_from and batch_transfer also require a confirmation above the threshold
+        #[ink::test]
+        fn large_transfer_confirmation_covers_every_send_path() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            asset.approve(accounts.alice, 500).unwrap();
+            assert_eq!(
+                asset.transfer_from(accounts.alice, accounts.bob, 500),
+                Err(Error::ConfirmationRequired)
+            );
+            assert_eq!(
+                asset.batch_transfer(vec![(accounts.bob, 60), (accounts.charlie, 60)]),
+                Err(Error::ConfirmationRequired)
+            );
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+            asset
+                .batch_transfer(vec![(accounts.bob, 50), (accounts.charlie, 50)])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(900));
+        }
+
+        // Test if a redeem above the threshold also requires a confirmation
+        #[ink::test]
+        fn large_redeem_requires_confirmation() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.redeem(150), Err(Error::ConfirmationRequired));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+            asset.redeem(100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if every approval is recorded in the allowance history
+        #[ink::test]
+        fn allowance_history_records_approvals() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            asset.approve(accounts.bob, 100).unwrap();
+            advance_blocks(1);
+            asset.approve(accounts.bob, 50).unwrap();
+            advance_blocks(1);
+            asset.approve(accounts.bob, 0).unwrap();
+
+            assert_eq!(asset.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(
+                asset.allowance_history(accounts.alice, accounts.bob),
+                vec![(0, 100), (1, 50), (2, 0)]
+            );
+            assert!(asset
+                .allowance_history(accounts.alice, accounts.charlie)
+                .is_empty());
+        }
+
+        // Test if the allowance history drops the oldest entries past the limit
+        #[ink::test]
+        fn allowance_history_is_bounded() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            for amount in 0..(ALLOWANCE_HISTORY_LIMIT as Balance + 2) {
+                asset.approve(accounts.bob, amount).unwrap();
+            }
+
+            let history = asset.allowance_history(accounts.alice, accounts.bob);
+            assert_eq!(history.len(), ALLOWANCE_HISTORY_LIMIT as usize);
+            assert_eq!(history[0], (0, 2));
+            assert_eq!(
+                history.last(),
+                Some(&(0, ALLOWANCE_HISTORY_LIMIT as Balance + 1))
+            );
+        }
+
+        // Test if opting in to a default frozen asset freezes the account
+        #[ink::test]
+        fn opt_in_to_default_frozen_asset_freezes_account() {
+            let accounts = default_accounts();
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                reserve: None,
+                clawback: None,
+                ..default_params()
+            });
+            assert!(!asset.is_frozen(accounts.alice));
+            assert!(asset.is_frozen(accounts.bob));
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            // frozen through the default, without an explicit status
+            assert_eq!(asset.frozen_holders.get(accounts.bob), None);
+            assert!(asset.is_frozen(accounts.bob));
+
+            // the freeze address can unfreeze the new holder
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            assert!(!asset.is_frozen(accounts.bob));
+            assert_eq!(asset.freeze(accounts.bob, false, 0), Err(Error::NotFrozen));
+        }
+
+        // Test if opting in to a default unfrozen asset leaves the account unfrozen
+        #[ink::test]
+        fn opt_in_to_default_unfrozen_asset_does_not_freeze_account() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert_eq!(asset.frozen_holders.get(accounts.bob), None);
+            assert!(!asset.is_frozen(accounts.bob));
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 2);
+        }
+
+        // Test if distribute splits the pool evenly between equal holders
+        #[ink::test]
+        fn distribute_splits_evenly() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+                set_caller(accounts.alice);
+                asset.transfer(holder, 100).unwrap();
+            }
+
+            asset
+                .distribute(50, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(125));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(125));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(750));
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 7);
+        }
+
+        // Test if distribute gives the rounding remainder to the last holder
+        #[ink::test]
+        fn distribute_assigns_remainder_to_last_holder() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for (holder, amount) in [(accounts.bob, 100), (accounts.charlie, 200)] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+                set_caller(accounts.alice);
+                asset.transfer(holder, amount).unwrap();
+            }
+
+            asset
+                .distribute(100, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(133));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(267));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(600));
+        }
+
+        // Test if distribute rejects an empty holder list and a zero pool
+        #[ink::test]
+        fn distribute_rejects_empty_holders_and_zero_pool() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            assert_eq!(asset.distribute(100, vec![]), Err(Error::NoHolders));
+            assert_eq!(
+                asset.distribute(0, vec![accounts.alice]),
+                Err(Error::ZeroAmount)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.distribute(100, vec![accounts.alice]),
+                Err(Error::NotReserveId)
+            );
+        }
+
+        // Test if holders can exit while paused when the emergency exit is enabled
+        #[ink::test]
+        fn emergency_exit_works_while_paused() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.pause().unwrap();
+            asset.set_emergency_exit(true).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.transfer(accounts.charlie, 100),
+                Err(Error::ContractPaused)
+            );
+            asset.emergency_exit(accounts.charlie).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(100));
+        }
+
+        // Test if holders can't exit when the emergency exit is disabled
+        #[ink::test]
+        fn emergency_exit_fails_when_disabled() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.pause().unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.emergency_exit(accounts.charlie),
+                Err(Error::EmergencyExitDisabled)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if frozen holders can't use the emergency exit
+        #[ink::test]
+        fn emergency_exit_respects_frozen_accounts() {
+            let accounts = default_accounts();
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                reserve: None,
+                clawback: None,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.set_emergency_exit(true).unwrap();
+
+            assert_eq!(
+                asset.emergency_exit(accounts.bob),
+                Err(Error::FrozenAccount)
+            );
+        }
+
+        // Test if the compact schema carries the SCALE encoded Transfer event
+        #[ink::test]
+        fn compact_transfer_event_round_trips() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                compact_events: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 3);
+            let event = &events[2];
+            // event signature, asset id and primary account
+            assert_eq!(event.topics.len(), 3);
+            let compact = match <Event as Decode>::decode(&mut &event.data[..]) {
+                Ok(Event::Compact(compact)) => compact,
+                _ => panic!("expected a Compact event"),
+            };
+            assert_eq!(compact.account, accounts.alice);
+            match <Event as Decode>::decode(&mut &compact.data[..]) {
+                Ok(Event::Transfer(transfer)) => {
+                    assert_eq!(transfer.sender, accounts.alice);
+                    assert_eq!(transfer.receiver, accounts.bob);
+                    assert_eq!(transfer.asset_id, asset.asset_id());
+                    assert_eq!(transfer.amount, 100);
+                }
+                _ => panic!("expected a Transfer event"),
+            }
+        }
+
+        // Test if a transfer to self leaves the balance unchanged
+        #[ink::test]
+        fn transfer_to_self_keeps_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            asset.transfer(accounts.alice, 400).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+            assert_eq!(
+                asset.transfer(accounts.alice, 1001),
+                Err(Error::NotEnoughBalance)
+            );
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 2);
+        }
+
+        // Test if balances stay exact when transfers to self and others are mixed
+        #[ink::test]
+        fn transfer_after_self_transfer_uses_fresh_balances() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            set_caller(accounts.bob);
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.transfer(accounts.alice, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(800));
+        }
+
+        // Test if a snapshot keeps the balances from before later transfers
+        #[ink::test]
+        fn balance_of_at_returns_snapshot_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            let first = asset.take_snapshot().unwrap();
+            asset.transfer(accounts.bob, 200).unwrap();
+            let second = asset.take_snapshot().unwrap();
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            assert_eq!(asset.balance_of_at(accounts.bob, first), Ok(100));
+            assert_eq!(asset.balance_of_at(accounts.alice, first), Ok(900));
+            assert_eq!(asset.balance_of_at(accounts.bob, second), Ok(300));
+            assert_eq!(asset.balance_of_at(accounts.alice, second), Ok(700));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(600));
+            assert_eq!(asset.balance_of_at(accounts.charlie, first), Ok(0));
+        }
+
+        // Test if the checkpoint search finds the balance between sparse balance changes
+        #[ink::test]
+        fn balance_of_at_searches_checkpoints() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            // bob receives 10 tokens after every third snapshot
+            for snapshot in 1..=12 {
+                asset.take_snapshot().unwrap();
+                if snapshot % 3 == 0 {
+                    asset.transfer(accounts.bob, 10).unwrap();
+                }
+            }
+
+            for snapshot in 1..=12 {
+                let expected = (snapshot - 1) / 3 * 10;
+                assert_eq!(
+                    asset.balance_of_at(accounts.bob, snapshot),
+                    Ok(expected as Balance)
+                );
+                assert_eq!(
+                    asset.balance_of_at(accounts.alice, snapshot),
+                    Ok(1000 - expected as Balance)
+                );
+            }
+            assert_eq!(asset.balance_of(accounts.bob), Ok(40));
+        }
+
+        // Test if unknown snapshots and non-manager snapshots are rejected
+        #[ink::test]
+        fn balance_of_at_rejects_unknown_snapshots() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            assert_eq!(
+                asset.balance_of_at(accounts.alice, 0),
+                Err(Error::InvalidSnapshot)
+            );
+            assert_eq!(asset.take_snapshot(), Ok(1));
+            assert_eq!(asset.balance_of_at(accounts.alice, 1), Ok(1000));
+            assert_eq!(
+                asset.balance_of_at(accounts.alice, 2),
+                Err(Error::InvalidSnapshot)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(asset.take_snapshot(), Err(Error::NotManagerId));
+        }
+
+        // Test if transfers out of the reserve must respect the minimum reserve
+        #[ink::test]
+        fn reserve_transfer_respects_min_reserve() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            asset.set_min_reserve(600).unwrap();
+            assert_eq!(
+                asset.transfer(accounts.bob, 401),
+                Err(Error::ReserveBelowMinimum)
+            );
+            asset.transfer(accounts.bob, 300).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(700));
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(600));
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.set_min_reserve(0), Err(Error::NotManagerId));
+        }
+
+        // Test if a transfer updates the last activity of both parties
+        #[ink::test]
+        fn transfer_updates_last_activity() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert_eq!(asset.last_activity_of(accounts.bob), None);
+
+            advance_blocks(3);
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.last_activity_of(accounts.alice), Some(3));
+            assert_eq!(asset.last_activity_of(accounts.bob), Some(3));
+
+            advance_blocks(2);
+            set_caller(accounts.bob);
+            asset.transfer(accounts.alice, 50).unwrap();
+            assert_eq!(asset.last_activity_of(accounts.alice), Some(5));
+            assert_eq!(asset.last_activity_of(accounts.bob), Some(5));
+            assert_eq!(asset.last_activity_of(accounts.charlie), None);
+        }
+
+        // Test if locked balance can only be transferred after its release
+        #[ink::test]
+        fn locked_balance_is_released_at_timestamp() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 500).unwrap();
+            // two blocks of 6 milliseconds each
+            asset.lock_until(accounts.bob, 300, 12).unwrap();
+            assert_eq!(asset.locked_balance(accounts.bob), 300);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.transfer(accounts.alice, 300),
+                Err(Error::BalanceLocked)
+            );
+            asset.transfer(accounts.alice, 200).unwrap();
+
+            advance_blocks(2);
+            assert_eq!(asset.locked_balance(accounts.bob), 0);
+            asset.transfer(accounts.alice, 300).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+        }
+
+        // Test if only the reserve can lock balances
+        #[ink::test]
+        fn lock_until_requires_reserve() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.lock_until(accounts.bob, 300, 12),
+                Err(Error::NotReserveId)
+            );
+        }
+
+        // Test if opt-ins are capped by max_holders
+        #[ink::test]
+        fn opt_in_respects_max_holders() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                reserve: None,
+                freeze: None,
+                clawback: None,
+                max_holders: Some(3),
+                ..default_params()
+            });
+            // the reserve counts as the first holder
+            assert_eq!(asset.opted_in_count(), 1);
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            assert_eq!(asset.opted_in_count(), 3);
+
+            set_caller(accounts.django);
+            assert_eq!(asset.opt_in(), Err(Error::MaxHoldersReached));
+
+            set_caller(accounts.bob);
+            asset.opt_out().unwrap();
+            set_caller(accounts.django);
+            asset.opt_in().unwrap();
+            assert_eq!(asset.opted_in_count(), 3);
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.opt_in(), Err(Error::MaxHoldersReached));
+        }
+
+        // Test if privileged actions are recorded in order in the audit log
+        #[ink::test]
+        fn audit_log_records_privileged_actions() {
+            let accounts = default_accounts();
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                reserve: None,
+                clawback: None,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert!(asset.audit_log().is_empty());
+
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            advance_blocks(1);
+            asset
+                .modify_asset(
+                    Some(accounts.charlie),
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    None,
+                )
+                .unwrap();
+            set_caller(accounts.charlie);
+            asset.accept_manager().unwrap();
+
+            assert_eq!(
+                asset.audit_log(),
+                vec![
+                    AuditEntry {
+                        actor: accounts.alice,
+                        action: AuditAction::Unfreeze,
+                        target: accounts.bob,
+                        block: 0,
+                    },
+                    AuditEntry {
+                        actor: accounts.alice,
+                        action: AuditAction::Modify,
+                        target: accounts.alice,
+                        block: 1,
+                    },
+                    AuditEntry {
+                        actor: accounts.charlie,
+                        action: AuditAction::Modify,
+                        target: accounts.charlie,
+                        block: 1,
+                    },
+                ]
+            );
+        }
+
+        // Test if the audit log drops the oldest entries past the limit
+        #[ink::test]
+        fn audit_log_is_bounded() {
+            let mut asset = create_asset();
+
+            for _ in 0..AUDIT_LOG_LIMIT {
+                asset.pause().unwrap();
+                asset.unpause().unwrap();
+            }
+
+            let log = asset.audit_log();
+            assert_eq!(log.len(), AUDIT_LOG_LIMIT as usize);
+            assert_eq!(log[0].action, AuditAction::Pause);
+            assert_eq!(
+                log.last().map(|entry| entry.action),
+                Some(AuditAction::Unpause)
+            );
+        }
+
+        // Test if the manager can force an opt-in so the account can receive transfers
+        #[ink::test]
+        fn force_opt_in_lets_account_receive_transfers() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            asset.force_opt_in(accounts.bob).unwrap();
+            assert!(asset.is_opted_in(accounts.bob));
+            assert_eq!(asset.opted_in_count(), 2);
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+            assert_eq!(asset.force_opt_in(accounts.bob), Err(Error::AlreadyOptedIn));
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 3);
+        }
+
+        // Test if only the manager can force an opt-in
+        #[ink::test]
+        fn force_opt_in_requires_manager() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.force_opt_in(accounts.charlie),
+                Err(Error::NotManagerId)
+            );
+            assert!(!asset.is_opted_in(accounts.charlie));
+        }
+
+        // Test if freezing and locking reduce the circulating supply but not the total
+        #[ink::test]
+        fn circulating_supply_excludes_reserve_frozen_and_locked() {
+            let accounts = default_accounts();
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                reserve: None,
+                clawback: None,
+                ..default_params()
+            });
+            for (holder, amount) in [(accounts.bob, 300), (accounts.charlie, 200)] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+                set_caller(accounts.alice);
+                asset.freeze(holder, false, 0).unwrap();
+                asset.transfer(holder, amount).unwrap();
+            }
+            assert_eq!(asset.circulating_supply(), 500);
+
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            assert_eq!(asset.circulating_supply(), 200);
+
+            asset.lock_until(accounts.charlie, 150, 12).unwrap();
+            assert_eq!(asset.circulating_supply(), 50);
+            assert_eq!(asset.total(), 1000);
+
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            assert_eq!(asset.circulating_supply(), 350);
+        }
+
+        // Test if transfer_from moves tokens and consumes the allowance
+        #[ink::test]
+        fn transfer_from_consumes_allowance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.approve(accounts.bob, 300).unwrap();
+
+            set_caller(accounts.bob);
+            asset
+                .transfer_from(accounts.alice, accounts.charlie, 200)
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(200));
+            assert_eq!(asset.allowance(accounts.alice, accounts.bob), 100);
+            assert_eq!(
+                asset.transfer_from(accounts.alice, accounts.charlie, 101),
+                Err(Error::InsufficientAllowance)
+            );
+        }
+
+        // Test if transfer_from fails on balance without touching a larger allowance
+        #[ink::test]
+        fn transfer_from_checks_balance_before_allowance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            set_caller(accounts.bob);
+            asset.approve(accounts.charlie, 500).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                asset.transfer_from(accounts.bob, accounts.charlie, 200),
+                Err(Error::NotEnoughBalance)
+            );
+            assert_eq!(asset.allowance(accounts.bob, accounts.charlie), 500);
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if asset_info returns every constructor argument
+        #[ink::test]
+        fn asset_info_matches_constructor_arguments() {
+            use ink_lang::codegen::Env;
+            let accounts = default_accounts();
+            let asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                metadata_hash: [0x1, 0x2, 0x3, 0x4],
+                manager: Some(accounts.bob),
+                reserve: Some(accounts.charlie),
+                freeze: Some(accounts.django),
+                clawback: Some(accounts.eve),
+                min_balance: 5,
+                ..default_params()
+            });
+
+            assert_eq!(
+                asset.asset_info(),
+                AssetInfo {
+                    asset_id: asset.env().account_id(),
+                    asset_name: "Test subsa".into(),
+                    unit_name: "TSSA".into(),
+                    total: 1000,
+                    decimals: 10,
+                    default_frozen: true,
+                    freezable: true,
+                    min_balance: 5,
+                    url: "www.test.com".into(),
+                    metadata_hash: [0x1, 0x2, 0x3, 0x4],
+                    creator_id: accounts.alice,
+                    manager_id: accounts.bob,
+                    reserve_id: accounts.charlie,
+                    freeze_id: accounts.django,
+                    clawback_id: accounts.eve,
+                }
+            );
+        }
+
+        // Test if get_asset_config returns the configuration of the asset page
+        #[ink::test]
+        fn get_asset_config_works() {
+            let accounts = default_accounts();
+            let asset = Subsa::new(AssetParams {
+                metadata_hash: [0x1, 0x2, 0x3, 0x4],
+                reserve: Some(accounts.charlie),
+                clawback: Some(accounts.eve),
+                ..default_params()
+            });
+
+            assert_eq!(
+                asset.get_asset_config(),
+                AssetConfig {
+                    asset_name: "Test subsa".into(),
+                    unit_name: "TSSA".into(),
+                    total: 1000,
+                    decimals: 10,
+                    default_frozen: false,
+                    url: "www.test.com".into(),
+                    metadata_hash: [0x1, 0x2, 0x3, 0x4],
+                    manager_id: accounts.alice,
+                    reserve_id: accounts.charlie,
+                    freeze_id: accounts.alice,
+                    clawback_id: accounts.eve,
+                    creator_id: accounts.alice,
+                }
+            );
+        }
+
+        // Test if the top holder follows transfers that shift the largest balance
+        #[ink::test]
+        fn top_holder_follows_transfers() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            assert!(asset.am_i_top_holder());
+
+            asset.transfer(accounts.bob, 600).unwrap();
+            assert!(!asset.am_i_top_holder());
+            assert!(asset.is_top_holder(accounts.bob));
+
+            set_caller(accounts.bob);
+            assert!(asset.am_i_top_holder());
+            asset.transfer(accounts.charlie, 450).unwrap();
+            assert!(!asset.am_i_top_holder());
+            assert!(asset.is_top_holder(accounts.charlie));
+            assert_eq!(asset.top_holder(), accounts.charlie);
+        }
+
+        // Test if the largest holder takes over when the top holder gives most of its
+        // balance to a smaller holder
+        #[ink::test]
+        fn top_holder_is_found_again_when_its_balance_drops() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie, accounts.django] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 400).unwrap();
+            asset.transfer(accounts.charlie, 350).unwrap();
+            assert!(asset.is_top_holder(accounts.bob));
+
+            set_caller(accounts.bob);
+            asset.transfer(accounts.django, 100).unwrap();
+            assert!(!asset.am_i_top_holder());
+            assert_eq!(asset.top_holder(), accounts.charlie);
+        }
+
+        // Test if minting and burning move the circulating supply but not the total
+        #[ink::test]
+        fn circulating_supply_tracks_mint_and_burn() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                max_supply: Some(1100),
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            assert_eq!(asset.circulating_supply(), 300);
+
+            set_caller(accounts.bob);
+            asset.burn(100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+            assert_eq!(asset.circulating_supply(), 200);
+            assert_eq!(asset.burn(201), Err(Error::NotEnoughBalance));
+
+            set_caller(accounts.alice);
+            asset.mint(accounts.bob, 60).unwrap();
+            assert_eq!(asset.circulating_supply(), 260);
+            assert_eq!(asset.balance_of(accounts.bob), Ok(260));
+            assert_eq!(asset.minted(), 1060);
+            assert_eq!(asset.total(), 1000);
+        }
+
+        // Test if minting stops at the max supply, and burning doesn't free up headroom
+        #[ink::test]
+        fn mint_is_capped_by_lifetime_minted_supply() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                max_supply: Some(1100),
+                ..default_params()
+            });
+            assert_eq!(asset.max_supply(), Some(1100));
+            assert_eq!(asset.minted(), 1000);
+
+            // exactly to the cap, then one unit over
+            asset.mint(accounts.alice, 100).unwrap();
+            assert_eq!(asset.minted(), 1100);
+            assert_eq!(asset.mint(accounts.alice, 1), Err(Error::SupplyCapExceeded));
+
+            asset.burn(50).unwrap();
+            assert_eq!(asset.minted(), 1100);
+            assert_eq!(asset.mint(accounts.alice, 1), Err(Error::SupplyCapExceeded));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1050));
+            assert_eq!(asset.verify_invariants(), Ok(()));
+        }
+
+        // Test if an asset without a max supply can mint beyond its initial supply
+        #[ink::test]
+        fn mint_is_uncapped_without_max_supply() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(asset.max_supply(), None);
+
+            asset.mint(accounts.alice, 5000).unwrap();
+            assert_eq!(asset.minted(), 6000);
+            assert_eq!(asset.total_supply(), 6000);
+            assert_eq!(asset.balance_of(accounts.alice), Ok(6000));
+            assert_eq!(
+                asset.mint(accounts.alice, Balance::MAX),
+                Err(Error::SupplyCapExceeded)
+            );
+        }
+
+        // Test if only the reserve can mint
+        #[ink::test]
+        fn mint_requires_reserve() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.mint(accounts.alice, 1), Err(Error::NotReserveId));
+        }
+
+        // Test if is_opted_in follows opt-in and opt-out
+        #[ink::test]
+        fn is_opted_in_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert!(asset.is_opted_in(accounts.alice));
+            assert!(!asset.is_opted_in(accounts.bob));
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert!(asset.is_opted_in(accounts.bob));
+            asset.opt_out().unwrap();
+            assert!(!asset.is_opted_in(accounts.bob));
+        }
+
+        // Test if the transfer fee decays from the start rate to the floor
+        #[ink::test]
+        fn fee_decays_linearly() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(asset.current_fee_bps(), 0);
+            asset.set_fee_decay(100, 20, 10, 20).unwrap();
+
+            advance_blocks(10);
+            assert_eq!(asset.current_fee_bps(), 100);
+            advance_blocks(5);
+            assert_eq!(asset.current_fee_bps(), 60);
+            advance_blocks(6);
+            assert_eq!(asset.current_fee_bps(), 20);
+
+            assert_eq!(
+                asset.set_fee_decay(10_001, 20, 10, 20),
+                Err(Error::InvalidFee)
+            );
+            assert_eq!(asset.set_fee_decay(20, 100, 10, 20), Err(Error::InvalidFee));
+            set_caller(accounts.bob);
+            assert_eq!(asset.set_fee_decay(0, 0, 0, 0), Err(Error::NotManagerId));
+        }
+
+        // Test if transfers pay the current fee to the reserve
+        #[ink::test]
+        fn transfer_pays_current_fee_to_reserve() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.set_fee_decay(100, 20, 10, 20).unwrap();
+            // transfers from the reserve are free
+            asset.transfer(accounts.bob, 500).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(500));
+
+            advance_blocks(15);
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 200).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(199));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(501));
+        }
+
+        // Test if a vault can opt in and receive tokens through the AsaToken trait
+        #[ink::test]
+        fn vault_opts_in_and_receives_through_trait() {
+            // mock of a vault contract calling the asset only through the trait
+            fn receive_into_vault(
+                token: &mut Subsa,
+                vault: AccountId,
+                from: AccountId,
+                amount: Balance,
+            ) -> Result<Balance, Error> {
+                if !<Subsa as AsaToken>::is_opted_in(token, vault) {
+                    set_caller(vault);
+                    <Subsa as AsaToken>::opt_in(token)?;
+                }
+                set_caller(from);
+                <Subsa as AsaToken>::transfer(token, vault, amount)?;
+                <Subsa as AsaToken>::balance_of(token, vault)
+            }
+
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(
+                receive_into_vault(&mut asset, accounts.django, accounts.alice, 100),
+                Ok(100)
+            );
+            // an opted in vault isn't opted in again
+            assert_eq!(
+                receive_into_vault(&mut asset, accounts.django, accounts.alice, 50),
+                Ok(150)
+            );
+            assert_eq!(asset.opted_in_count(), 2);
+        }
+
+        // Test if ownership is reported in basis points of the issued supply
+        #[ink::test]
+        fn ownership_bps_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 500).unwrap();
+
+            assert_eq!(asset.ownership_bps(accounts.bob), 5000);
+            assert_eq!(asset.ownership_bps(accounts.charlie), 0);
+
+            // shares follow the issued supply as it is minted and burned
+            asset.mint(accounts.alice, 1000).unwrap();
+            assert_eq!(asset.ownership_bps(accounts.bob), 2500);
+            asset.burn(1500).unwrap();
+            assert_eq!(asset.ownership_bps(accounts.bob), 10_000);
+        }
+
+        // Test if strict clawback only revokes from opted in accounts
+        #[ink::test]
+        fn strict_clawback_requires_opted_in_target() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            // bob still holds tokens after opting out
+            asset.opt_out_account(accounts.bob);
+            set_caller(accounts.alice);
+            asset.set_strict_clawback(true).unwrap();
+            assert_eq!(
+                asset.revoke_asset(accounts.alice, accounts.bob, 40),
+                Err(Error::NotOptedIn)
+            );
+
+            asset.set_strict_clawback(false).unwrap();
+            assert_eq!(asset.revoke_asset(accounts.alice, accounts.bob, 40), Ok(()));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(940));
+        }
+
+        // Test if a batch transfer moves tokens to every receiver
+        #[ink::test]
+        fn batch_transfer_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset
+                .batch_transfer(vec![(accounts.bob, 100), (accounts.charlie, 200)])
+                .unwrap();
+
+            assert_eq!(asset.balance_of(accounts.alice), Ok(700));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(200));
+        }
+
+        // Test if batch operations reject a receiver listed twice
+        #[ink::test]
+        fn batch_operations_reject_duplicate_recipient() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            assert_eq!(
+                asset.batch_transfer(vec![(accounts.bob, 10), (accounts.bob, 20)]),
+                Err(Error::DuplicateRecipient)
+            );
+            assert_eq!(
+                asset.distribute(10, vec![accounts.bob, accounts.bob]),
+                Err(Error::DuplicateRecipient)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if an invalid entry at the end of a batch leaves every balance untouched
+        #[ink::test]
+        fn batch_transfer_validates_before_mutation() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            let emitted_events = ink_env::test::recorded_events().count();
+
+            assert_eq!(
+                asset.batch_transfer(vec![(accounts.bob, 100), (accounts.charlie, 100)]),
+                Err(Error::NotOptedIn)
+            );
+            assert_eq!(
+                asset.batch_transfer(vec![(accounts.bob, 600), (accounts.alice, 600)]),
+                Err(Error::NotEnoughBalance)
+            );
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+            assert_eq!(ink_env::test::recorded_events().count(), emitted_events);
+        }
+
+        // Test if set_metadata updates URL and hash together with a single event
+        #[ink::test]
+        fn set_metadata_updates_both_fields() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            let emitted_events = ink_env::test::recorded_events().count();
+
+            asset
+                .set_metadata(String::from("https://example.com/v2"), [9, 8, 7, 6])
+                .unwrap();
+            assert_eq!(asset.url(), String::from("https://example.com/v2"));
+            assert_eq!(asset.metadata_hash(), [9, 8, 7, 6]);
+
+            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), emitted_events + 1);
+            let event = <Event as Decode>::decode(&mut &events[emitted_events].data[..]).unwrap();
+            assert!(matches!(
+                event,
+                Event::MetadataUpdated(MetadataUpdated {
+                    new_metadata_hash: [9, 8, 7, 6],
+                    ..
+                })
+            ));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.set_metadata(String::from("https://evil.com"), [0; 4]),
+                Err(Error::NotManagerId)
+            );
+        }
+
+        // Test if the reserve releases its own tokens to a holder
+        #[ink::test]
+        fn release_moves_reserve_tokens() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            asset.release(accounts.bob, 300).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(700));
+            assert_eq!(asset.circulating_supply(), 300);
+
+            assert_eq!(
+                asset.release(accounts.bob, 701),
+                Err(Error::NotEnoughBalance)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(asset.release(accounts.bob, 1), Err(Error::NotReserveId));
+        }
+
+        // Test if the reserve can't release tokens to a frozen holder
+        #[ink::test]
+        fn release_rejects_frozen_recipient() {
+            let accounts = default_accounts();
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            assert_eq!(asset.release(accounts.bob, 100), Err(Error::FrozenAccount));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+        }
+
+        // Test if a registry rejects token movements but keeps opt-in and freeze working
+        #[ink::test]
+        fn registry_mode_only_disables_token_movements() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                asset_name: "Test registry".into(),
+                unit_name: "TREG".into(),
+                total: 0,
+                decimals: 0,
+                default_frozen: true,
+                registry_mode: true,
+                ..default_params()
+            });
+            assert!(asset.registry_mode());
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert!(asset.is_opted_in(accounts.bob));
+            assert!(asset.is_frozen(accounts.bob));
+
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            assert!(!asset.is_frozen(accounts.bob));
+            asset.freeze(accounts.bob, true, 0).unwrap();
+
+            assert_eq!(asset.transfer(accounts.bob, 0), Err(Error::RegistryMode));
+            assert_eq!(asset.mint(accounts.bob, 1), Err(Error::RegistryMode));
+            assert_eq!(asset.burn(1), Err(Error::RegistryMode));
+            assert_eq!(
+                asset.batch_transfer(vec![(accounts.bob, 0)]),
+                Err(Error::RegistryMode)
+            );
+            asset.set_emergency_exit(true).unwrap();
+            assert_eq!(asset.emergency_exit(accounts.bob), Err(Error::RegistryMode));
+        }
+
+        // Test if a transfer of the exact balance drains the sender
+        #[ink::test]
+        fn transfer_exact_balance_drains_sender() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            asset.transfer(accounts.bob, 1000).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(0));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(1000));
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.transfer(accounts.alice, 1001),
+                Err(Error::NotEnoughBalance)
+            );
+        }
+
+        // Test if transfers accumulate inflows and outflows
+        #[ink::test]
+        fn net_flow_tracks_transfers() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.transfer(accounts.bob, 200).unwrap();
+            set_caller(accounts.bob);
+            asset.transfer(accounts.alice, 120).unwrap();
+
+            assert_eq!(asset.net_flow(accounts.bob), (500, 120));
+            assert_eq!(asset.net_flow(accounts.alice), (120, 500));
+            assert_eq!(asset.net_flow(accounts.charlie), (0, 0));
+        }
+
+        // Test if freeze events carry the previous frozen status
+        #[ink::test]
+        fn freeze_events_carry_previous_status() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            asset.freeze(accounts.bob, true, 0).unwrap();
+
+            let transitions = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Freeze(Freeze {
+                            freeze, previous, ..
+                        })) => Some((previous, freeze)),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(
+                transitions,
+                vec![(false, true), (true, false), (false, true)]
+            );
+        }
+
+        // Test if transfers to accounts that haven't opted in are escrowed and claimable
+        #[ink::test]
+        fn escrowed_transfer_can_be_claimed() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                allow_escrow: true,
+                ..default_params()
+            });
+
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(900));
+            assert_eq!(asset.pending_balance(accounts.bob), 100);
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.claim(), Err(Error::NotOptedIn));
+            asset.opt_in().unwrap();
+            asset.claim().unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+            assert_eq!(asset.pending_balance(accounts.bob), 0);
+            assert_eq!(asset.claim(), Err(Error::ZeroAmount));
+        }
+
+        // Test if transfers to accounts that haven't opted in fail without escrow
+        #[ink::test]
+        fn escrow_disabled_by_default() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            assert_eq!(asset.transfer(accounts.bob, 100), Err(Error::NotOptedIn));
+            assert_eq!(asset.pending_balance(accounts.bob), 0);
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+        }
+
+        // Test if a frozen account can't claim, and a claim emits a transfer from the escrow account
+        #[ink::test]
+        fn escrow_claim_checks_frozen_and_emits_transfer() {
+            let accounts = default_accounts();
+            let mut asset = create_escrow_asset();
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(asset.claim(), Err(Error::FrozenAccount));
+            assert_eq!(asset.pending_balance(accounts.bob), 100);
+
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            set_caller(accounts.bob);
+            asset.claim().unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+
+            let escrow = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let transfers = recorded_transfers();
+            assert_eq!(
+                transfers[transfers.len() - 2..],
+                [(accounts.alice, escrow, 100), (escrow, accounts.bob, 100)]
+            );
+        }
+
+        // Test if a commit is rejected for the zero address, a zero amount or a window
+        // overflowing the block number
+        #[ink::test]
+        fn commit_large_transfer_validates_input() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(
+                asset.commit_large_transfer(AccountId::from([0x0; 32]), 500),
+                Err(Error::ZeroAddress)
+            );
+            assert_eq!(
+                asset.commit_large_transfer(accounts.bob, 0),
+                Err(Error::ZeroAmount)
+            );
+
+            asset
+                .set_large_transfer_threshold(Some(100), BlockNumber::MAX)
+                .unwrap();
+            asset.set_finality_delay(1).unwrap();
+            assert_eq!(
+                asset.commit_large_transfer(accounts.bob, 500),
+                Err(Error::Overflow)
+            );
+            assert_eq!(
+                asset.confirm_large_transfer(),
+                Err(Error::NoPendingTransfer)
+            );
+        }
+
+        // Test if a committed large transfer waits for the finality delay
+        #[ink::test]
+        fn large_transfer_waits_for_finality_delay() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+            asset.set_finality_delay(5).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            set_caller(accounts.alice);
+            asset.commit_large_transfer(accounts.bob, 500).unwrap();
+            advance_blocks(4);
+            assert_eq!(asset.confirm_large_transfer(), Err(Error::TransferNotFinal));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+
+            advance_blocks(1);
+            asset.confirm_large_transfer().unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(500));
+        }
+
+        // Test if the clawback address can force a transfer between frozen holders
+        #[ink::test]
+        fn force_transfer_works() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.freeze(accounts.bob, true, 0).unwrap();
+
+            asset
+                .force_transfer(accounts.bob, accounts.charlie, 200)
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(200));
+            assert_eq!(
+                asset.force_transfer(accounts.bob, accounts.charlie, 101),
+                Err(Error::NotEnoughBalance)
+            );
+        }
+
+        // Test if only the clawback address can force a transfer
+        #[ink::test]
+        fn force_transfer_rejects_non_clawback_caller() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            assert_eq!(
+                asset.force_transfer(accounts.alice, accounts.bob, 100),
+                Err(Error::NotClawbackId)
+            );
+        }
+
+        // Test if a forced transfer requires an opted in destination
+        #[ink::test]
+        fn force_transfer_rejects_destination_not_opted_in() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+
+            assert_eq!(
+                asset.force_transfer(accounts.alice, accounts.bob, 100),
+                Err(Error::NotOptedIn)
+            );
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+        }
+
+        // Test if the invariants hold across freezes, locks, mints and burns
+        #[ink::test]
+        fn verify_invariants_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.burn(100).unwrap();
+            assert_eq!(asset.verify_invariants(), Ok(()));
+
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            asset.lock_until(accounts.alice, 200, 100).unwrap();
+            asset.mint(accounts.alice, 500).unwrap();
+            assert_eq!(asset.verify_invariants(), Ok(()));
+
+            asset
+                .revoke_asset(accounts.alice, accounts.bob, 300)
+                .unwrap();
+            asset.burn(1000).unwrap();
+            assert_eq!(asset.verify_invariants(), Ok(()));
+            assert_eq!(asset.total_supply(), 400);
+        }
+
+        /// Opts bob in to a new asset and returns the `frozen` field of the last OptIn event.
+        fn opt_in_event_frozen(default_frozen: bool) -> Option<bool> {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::OptIn(OptIn { frozen, .. })) => Some(frozen),
+                        _ => None,
+                    },
+                )
+                .last()
+        }
+
+        // Test if opting in to a default frozen asset emits a frozen opt in event
+        #[ink::test]
+        fn opt_in_event_reports_frozen_holder() {
+            assert_eq!(opt_in_event_frozen(true), Some(true));
+        }
+
+        // Test if opting in to a normal asset emits an unfrozen opt in event
+        #[ink::test]
+        fn opt_in_event_reports_unfrozen_holder() {
+            assert_eq!(opt_in_event_frozen(false), Some(false));
+        }
+
+        // Test if a receiver pulls the tokens assigned to it
+        #[ink::test]
+        fn assign_then_pull_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.assign(accounts.bob, 100).unwrap();
+            asset.assign(accounts.bob, 50).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(850));
+            assert_eq!(asset.assigned(accounts.alice, accounts.bob), 150);
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.pull(accounts.alice), Err(Error::NotOptedIn));
+            asset.opt_in().unwrap();
+            asset.pull(accounts.alice).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(150));
+            assert_eq!(asset.assigned(accounts.alice, accounts.bob), 0);
+            assert_eq!(asset.pull(accounts.alice), Err(Error::ZeroAmount));
+        }
+
+        // Test if a sender can cancel an assignment that hasn't been pulled
+        #[ink::test]
+        fn cancel_unpulled_assignment_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.assign(accounts.bob, 100).unwrap();
+
+            asset.cancel_assignment(accounts.bob).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+            assert_eq!(asset.assigned(accounts.alice, accounts.bob), 0);
+            assert_eq!(
+                asset.cancel_assignment(accounts.bob),
+                Err(Error::ZeroAmount)
+            );
+        }
+
+        // Test if a frozen account can't assign, nor have its assignments pulled, and if
+        // assignments pay the transfer fee
+        #[ink::test]
+        fn assign_and_pull_respect_freeze_and_fee() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.set_transfer_fee(100).unwrap();
+
+            set_caller(accounts.bob);
+            asset.assign(accounts.charlie, 100).unwrap();
+            assert_eq!(asset.assigned(accounts.bob, accounts.charlie), 99);
+            assert_eq!(asset.balance_of(accounts.alice), Ok(701));
+
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.assign(accounts.charlie, 100),
+                Err(Error::FrozenAccount)
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(asset.pull(accounts.bob), Err(Error::FrozenAccount));
+
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            set_caller(accounts.charlie);
+            asset.pull(accounts.bob).unwrap();
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(99));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+
+            let escrow = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let transfers = recorded_transfers();
+            assert_eq!(
+                transfers[transfers.len() - 3..],
+                [
+                    (accounts.bob, escrow, 99),
+                    (accounts.bob, accounts.alice, 1),
+                    (escrow, accounts.charlie, 99)
+                ]
+            );
+        }
+
+        // Test if locking the supply disables minting and burning for good
+        #[ink::test]
+        fn lock_supply_disables_mint_and_burn() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.burn(100).unwrap();
+            assert!(!asset.is_supply_locked());
+
+            asset.lock_supply().unwrap();
+            assert!(asset.is_supply_locked());
+            assert_eq!(asset.mint(accounts.alice, 100), Err(Error::SupplyLocked));
+            assert_eq!(asset.burn(100), Err(Error::SupplyLocked));
+            assert_eq!(asset.lock_supply(), Err(Error::SupplyLocked));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(900));
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.lock_supply(), Err(Error::NotManagerId));
+        }
+
+        // Test if transfers pass through untouched without a fee
+        #[ink::test]
+        fn zero_transfer_fee_passes_through() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.set_transfer_fee(0).unwrap();
+            asset.transfer(accounts.bob, 500).unwrap();
+
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 333).unwrap();
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(333));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(500));
+        }
+
+        // Test if a flat transfer fee is split off to the reserve, rounding down
+        #[ink::test]
+        fn flat_transfer_fee_splits_to_reserve() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.set_transfer_fee(250).unwrap();
+            asset.transfer(accounts.bob, 500).unwrap();
+            let emitted_events = ink_env::test::recorded_events().count();
+
+            // 2.5% of 333 is 8.325, rounded down to 8
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 333).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(167));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(325));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(508));
+
+            // one event for the net amount and one for the fee
+            let amounts = ink_env::test::recorded_events()
+                .skip(emitted_events)
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Transfer(Transfer {
+                            receiver, amount, ..
+                        })) => Some((receiver, amount)),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(amounts, vec![(accounts.charlie, 325), (accounts.alice, 8)]);
+        }
+
+        // Test if a transfer fee above 10000 bps is rejected
+        #[ink::test]
+        fn transfer_fee_above_max_is_rejected() {
+            let mut asset = create_asset();
+            assert_eq!(asset.set_transfer_fee(10_001), Err(Error::InvalidFee));
+            assert_eq!(asset.set_transfer_fee(10_000), Ok(()));
+            assert_eq!(asset.current_fee_bps(), 10_000);
+        }
+
+        // Test if a batch transfer to 100 receivers credits each of them
+        #[ink::test]
+        fn batch_transfer_to_many_receivers() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            let receivers: Vec<AccountId> = (10..110).map(|i| AccountId::from([i; 32])).collect();
+            for receiver in &receivers {
+                set_caller(*receiver);
+                asset.opt_in().unwrap();
+            }
+
+            set_caller(accounts.alice);
+            asset
+                .batch_transfer(receivers.iter().map(|receiver| (*receiver, 9)).collect())
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(100));
+            for receiver in &receivers {
+                assert_eq!(asset.balance_of(*receiver), Ok(9));
+            }
+
+            // an over budget batch changes nothing
+            assert_eq!(
+                asset.batch_transfer(receivers.iter().map(|receiver| (*receiver, 2)).collect()),
+                Err(Error::NotEnoughBalance)
+            );
+            assert_eq!(asset.balance_of(accounts.alice), Ok(100));
+            assert_eq!(asset.balance_of(receivers[0]), Ok(9));
+        }
+
+        /// Creates an asset whose names the manager can change.
+        fn create_renamable_asset() -> Subsa {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            Subsa::new(AssetParams {
+                names_mutable: true,
+                ..default_params()
+            })
+        }
+
+        // Test if the manager can rename an asset with mutable names
+        #[ink::test]
+        fn rename_works() {
+            let accounts = default_accounts();
+            let mut asset = create_renamable_asset();
+
+            asset.rename(Some("Rebranded".into()), None).unwrap();
+            assert_eq!(asset.asset_name(), String::from("Rebranded"));
+            assert_eq!(asset.unit_name(), String::from("TSSA"));
+            asset.rename(None, Some("RBR".into())).unwrap();
+            assert_eq!(asset.unit_name(), String::from("RBR"));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.rename(Some("Stolen".into()), None),
+                Err(Error::NotManagerId)
+            );
+        }
+
+        // Test if an asset with immutable names can't be renamed
+        #[ink::test]
+        fn rename_rejects_immutable_names() {
+            let mut asset = create_asset();
+            assert_eq!(
+                asset.rename(Some("Rebranded".into()), None),
+                Err(Error::NamesImmutable)
+            );
+            assert_eq!(asset.asset_name(), String::from("Test subsa"));
+        }
+
+        // Test if an asset can't be renamed to an empty name
+        #[ink::test]
+        fn rename_rejects_empty_name() {
+            let mut asset = create_renamable_asset();
+            assert_eq!(
+                asset.rename(Some("Rebranded".into()), Some(String::new())),
+                Err(Error::EmptyName)
+            );
+            assert_eq!(asset.asset_name(), String::from("Test subsa"));
+        }
+
+        // Test if every privileged action maps to the role gating it
+        #[ink::test]
+        fn required_role_works() {
+            let asset = create_asset();
+            let expected = [
+                (AdminAction::Modify, RoleKind::Manager),
+                (AdminAction::ProposeManager, RoleKind::Manager),
+                (AdminAction::SetReconfigDelay, RoleKind::Manager),
+                (AdminAction::CancelPending, RoleKind::Manager),
+                (AdminAction::ClearRole, RoleKind::Manager),
+                (AdminAction::GrantRole, RoleKind::Manager),
+                (AdminAction::RevokeRole, RoleKind::Manager),
+                (AdminAction::Rename, RoleKind::Manager),
+                (AdminAction::SetMetadata, RoleKind::Manager),
+                (AdminAction::Destroy, RoleKind::Manager),
+                (AdminAction::Pause, RoleKind::Manager),
+                (AdminAction::Unpause, RoleKind::Manager),
+                (AdminAction::LockSupply, RoleKind::Manager),
+                (AdminAction::ForceOptIn, RoleKind::Manager),
+                (AdminAction::PruneOptIn, RoleKind::Manager),
+                (AdminAction::TakeSnapshot, RoleKind::Manager),
+                (AdminAction::DepositDividend, RoleKind::Manager),
+                (AdminAction::SetEmergencyExit, RoleKind::Manager),
+                (AdminAction::SetLargeTransferThreshold, RoleKind::Manager),
+                (AdminAction::SetFinalityDelay, RoleKind::Manager),
+                (AdminAction::SetStrictClawback, RoleKind::Manager),
+                (AdminAction::SetMinReserve, RoleKind::Manager),
+                (AdminAction::SetTransferFee, RoleKind::Manager),
+                (AdminAction::SetTransferHook, RoleKind::Manager),
+                (AdminAction::SetTransferCooldown, RoleKind::Manager),
+                (AdminAction::SetEscrowDeadline, RoleKind::Manager),
+                (AdminAction::ReclaimPending, RoleKind::Manager),
+                (AdminAction::Mint, RoleKind::Reserve),
+                (AdminAction::BurnFrom, RoleKind::Reserve),
+                (AdminAction::Distribute, RoleKind::Reserve),
+                (AdminAction::Release, RoleKind::Reserve),
+                (AdminAction::LockBalance, RoleKind::Reserve),
+                (AdminAction::Freeze, RoleKind::Freeze),
+                (AdminAction::SetFreezeExempt, RoleKind::Freeze),
+                (AdminAction::SetFreezeDefaultOverride, RoleKind::Freeze),
+                (AdminAction::Revoke, RoleKind::Clawback),
+                (AdminAction::ForceTransfer, RoleKind::Clawback),
+            ];
+            for (action, role) in expected {
+                assert_eq!(asset.required_role(action), role);
+            }
+        }
+
+        /// Creates an asset whose modify and destroy need two of alice, bob and charlie.
+        fn create_multi_authority_asset() -> Subsa {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            Subsa::new(AssetParams {
+                co_managers: vec![accounts.bob, accounts.charlie],
+                approval_threshold: 2,
+                ..default_params()
+            })
+        }
+
+        // Test if a proposed modification executes once the threshold is reached
+        #[ink::test]
+        fn multi_authority_modify_executes_at_threshold() {
+            let accounts = default_accounts();
+            let mut asset = create_multi_authority_asset();
+            let action = PendingAction::Modify {
+                manager: Some(accounts.alice),
+                reserve: Some(accounts.alice),
+                freeze: Some(accounts.django),
+                clawback: Some(accounts.alice),
+            };
+
+            let action_id = asset.propose_action(action).unwrap();
+            assert_eq!(asset.approval_count(action_id), 1);
+            assert_eq!(asset.freeze_id(), accounts.alice);
+
+            set_caller(accounts.charlie);
+            asset.approve_action(action_id).unwrap();
+            assert_eq!(asset.freeze_id(), accounts.django);
+            assert_eq!(asset.approve_action(action_id), Err(Error::NoSuchProposal));
+        }
+
+        // Test if actions without enough approvals don't execute
+        #[ink::test]
+        fn multi_authority_requires_enough_approvals() {
+            let accounts = default_accounts();
+            let mut asset = create_multi_authority_asset();
+            assert_eq!(asset.destroy_asset(), Err(Error::ApprovalRequired));
+            assert_eq!(
+                asset.modify_asset(None, None, None, None),
+                Err(Error::ApprovalRequired)
+            );
+
+            let action_id = asset.propose_action(PendingAction::Destroy).unwrap();
+            assert_eq!(asset.approval_count(action_id), 1);
+            set_caller(accounts.django);
+            assert_eq!(asset.approve_action(action_id), Err(Error::NotManagerId));
+            assert_eq!(asset.approval_count(action_id), 1);
+        }
+
+        // Test if a manager can't approve the same action twice
+        #[ink::test]
+        fn multi_authority_rejects_duplicate_approval() {
+            let mut asset = create_multi_authority_asset();
+            let action_id = asset.propose_action(PendingAction::Destroy).unwrap();
+
+            assert_eq!(asset.approve_action(action_id), Err(Error::AlreadyApproved));
+            assert_eq!(asset.approval_count(action_id), 1);
+        }
+
+        // Test if batch reads match the individual balance and frozen queries
+        #[ink::test]
+        fn batch_reads_match_individual_queries() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.charlie, 250).unwrap();
+
+            let holders = vec![accounts.alice, accounts.bob, accounts.charlie];
+            let balances = holders
+                .iter()
+                .map(|holder| asset.balance_of(*holder).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(asset.balances_of(holders.clone()), Ok(balances));
+            assert_eq!(asset.balances_of(vec![accounts.django]), Ok(vec![0]));
+            let frozen = holders
+                .iter()
+                .map(|holder| asset.is_frozen(*holder))
+                .collect::<Vec<_>>();
+            assert_eq!(asset.frozen_of(holders), Ok(frozen));
+
+            assert_eq!(
+                asset.balances_of(vec![accounts.bob; 129]),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(
+                asset.frozen_of(vec![accounts.bob; 129]),
+                Err(Error::BatchTooLarge)
+            );
+        }
+
+        // Test if distribution shares are paid to the payout address of their holder
+        #[ink::test]
+        fn distribute_pays_to_payout_address() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.django);
+            asset.opt_in().unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in_with_payout(accounts.django).unwrap();
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.charlie, 100).unwrap();
+
+            assert_eq!(asset.payout_address_of(accounts.bob), accounts.django);
+            assert_eq!(asset.payout_address_of(accounts.charlie), accounts.charlie);
+            asset
+                .distribute(50, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+            assert_eq!(asset.balance_of(accounts.django), Ok(25));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(125));
+
+            // resetting the payout address pays the holder again
+            set_caller(accounts.bob);
+            asset.set_payout_address(None).unwrap();
+            assert_eq!(asset.payout_address_of(accounts.bob), accounts.bob);
+            set_caller(accounts.alice);
+            asset
+                .distribute(50, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(122));
+        }
+
+        // Test if opting out to the reserve sweeps the remaining balance
+        #[ink::test]
+        fn opt_out_to_reserve_sweeps_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            set_caller(accounts.bob);
+            asset.opt_out_to(accounts.alice).unwrap();
+            assert!(!asset.is_opted_in(accounts.bob));
+            assert_eq!(asset.balances_of(vec![accounts.bob]), Ok(vec![0]));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+        }
+
+        // Test if opting out to an account that hasn't opted in is rejected
+        #[ink::test]
+        fn opt_out_to_rejects_target_not_opted_in() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            assert_eq!(asset.opt_out_to(accounts.charlie), Err(Error::NotOptedIn));
+            assert!(asset.is_opted_in(accounts.bob));
+        }
+
+        // Test if an account without balance opts out without a transfer
+        #[ink::test]
+        fn opt_out_to_with_zero_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            let emitted_events = ink_env::test::recorded_events().count();
+
+            asset.opt_out_to(accounts.alice).unwrap();
+            assert!(!asset.is_opted_in(accounts.bob));
+            // only the opt out event is emitted
+            assert_eq!(ink_env::test::recorded_events().count(), emitted_events + 1);
+        }
+
+        // Test if a controller contract can freeze through the delegation
+        #[ink::test]
+        fn controller_can_act_as_freeze_address() {
+            // mock of a governance contract forwarding a freeze to the asset
+            fn governance_freeze(
+                asset: &mut Subsa,
+                governance: AccountId,
+                account: AccountId,
+            ) -> Result<(), Error> {
+                set_caller(governance);
+                asset.freeze(account, true, 0)
+            }
+
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+
+            assert_eq!(
+                governance_freeze(&mut asset, accounts.eve, accounts.bob),
+                Err(Error::NotFreezeId)
+            );
+            set_caller(accounts.alice);
+            asset.delegate_to(Some(accounts.eve)).unwrap();
+            assert_eq!(asset.controller(), Some(accounts.eve));
+            governance_freeze(&mut asset, accounts.eve, accounts.bob).unwrap();
+            assert!(asset.is_frozen(accounts.bob));
+
+            // a cleared controller loses the delegation
+            set_caller(accounts.alice);
+            asset.delegate_to(None).unwrap();
+            assert_eq!(asset.controller(), None);
+            set_caller(accounts.eve);
+            assert_eq!(
+                asset.freeze(accounts.bob, false, 0),
+                Err(Error::NotFreezeId)
+            );
+        }
+
+        /// Returns the `(sender, receiver, amount)` of every `Transfer` event emitted so far.
+        fn recorded_transfers() -> Vec<(AccountId, AccountId, Balance)> {
+            ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Transfer(Transfer {
+                            sender,
+                            receiver,
+                            amount,
+                            ..
+                        })) => Some((sender, receiver, amount)),
+                        _ => None,
+                    },
+                )
+                .collect()
+        }
+
+        // Test if transfer events carry the transferred amount, from and to the zero address
+        // for mints and burns
+        #[ink::test]
+        fn transfer_events_carry_amount() {
+            let accounts = default_accounts();
+            let zero = AccountId::from([0x0; 32]);
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.burn(200).unwrap();
+            asset.mint(accounts.bob, 150).unwrap();
+            assert_eq!(
+                recorded_transfers(),
+                vec![
+                    (accounts.alice, accounts.bob, 300),
+                    (accounts.alice, zero, 200),
+                    (zero, accounts.bob, 150),
+                ]
+            );
+        }
+
+        // Test if renouncing clawback disables revoke and clawback reconfiguration for good
+        #[ink::test]
+        fn renounce_clawback_is_permanent() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            asset.renounce_clawback().unwrap();
+            assert!(asset.clawback_renounced());
+            assert_eq!(asset.clawback_id(), AccountId::from([0x0; 32]));
+            assert_eq!(
+                asset.revoke_asset(accounts.alice, accounts.bob, 50),
+                Err(Error::ClawbackRenounced)
+            );
+            assert_eq!(
+                asset.force_transfer(accounts.bob, accounts.alice, 50),
+                Err(Error::ClawbackRenounced)
+            );
+            assert_eq!(
+                asset.modify_asset(
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.alice)
+                ),
+                Err(Error::ClawbackRenounced)
+            );
+            assert_eq!(asset.renounce_clawback(), Err(Error::ClawbackRenounced));
+
+            // the other authorities can still be changed
+            asset
+                .modify_asset(
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.bob),
+                    None,
+                )
+                .unwrap();
+            assert_eq!(asset.freeze_id(), accounts.bob);
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if renouncing clawback emits a modify event with the zero clawback address
+        #[ink::test]
+        fn renounce_clawback_emits_modify() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.renounce_clawback().unwrap();
+
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as Decode>::decode(&mut &last.data[..]),
+                Ok(Event::Modify(Modify {
+                    manager_id,
+                    clawback_id,
+                    ..
+                })) if manager_id == accounts.alice && clawback_id == AccountId::from([0x0; 32])
+            ));
+        }
+
+        // Test if renouncing the freeze authority blocks freezing and freeze reconfiguration
+        #[ink::test]
+        fn renounce_freeze_blocks_freezing() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            asset.renounce_freeze().unwrap();
+            assert!(asset.freeze_renounced());
+            assert_eq!(asset.freeze_id(), AccountId::from([0x0; 32]));
+            assert_eq!(
+                asset.freeze(accounts.bob, false, 0),
+                Err(Error::FreezeRenounced)
+            );
+            assert_eq!(
+                asset.modify_asset(
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.alice)
+                ),
+                Err(Error::FreezeRenounced)
+            );
+        }
+
+        // Test if renouncing management blocks every authority reconfiguration
+        #[ink::test]
+        fn renounce_management_blocks_modify() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.delegate_to(Some(accounts.eve)).unwrap();
+
+            asset.renounce_management().unwrap();
+            assert!(asset.management_renounced());
+            assert_eq!(asset.manager_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.controller(), None);
+            assert_eq!(
+                asset.modify_asset(None, None, None, None),
+                Err(Error::ManagementRenounced)
+            );
+            set_caller(accounts.eve);
+            assert_eq!(
+                asset.modify_asset(None, None, None, None),
+                Err(Error::ManagementRenounced)
+            );
+        }
+
+        // Test if a single manager can't renounce management while co-managers must approve
+        #[ink::test]
+        fn renounce_management_requires_approval() {
+            let accounts = default_accounts();
+            let mut asset = create_multi_authority_asset();
+            assert_eq!(asset.renounce_management(), Err(Error::ApprovalRequired));
+            assert!(!asset.management_renounced());
+
+            let action_id = asset
+                .propose_action(PendingAction::ClearRole {
+                    role: RoleKind::Manager,
+                })
+                .unwrap();
+            set_caller(accounts.bob);
+            asset.approve_action(action_id).unwrap();
+            assert!(asset.management_renounced());
+            assert_eq!(asset.manager_id(), AccountId::from([0x0; 32]));
+        }
+
+        // Test if renouncing the freeze and clawback authorities waits for the timelock
+        #[ink::test]
+        fn renounce_freeze_and_clawback_wait_for_delay() {
+            let mut asset = create_asset();
+            asset.set_reconfig_delay(3).unwrap();
+            assert_eq!(asset.renounce_freeze(), Err(Error::ApprovalRequired));
+            assert_eq!(asset.renounce_clawback(), Err(Error::ApprovalRequired));
+
+            let freeze = asset
+                .propose_action(PendingAction::ClearRole {
+                    role: RoleKind::Freeze,
+                })
+                .unwrap();
+            let clawback = asset
+                .propose_action(PendingAction::ClearRole {
+                    role: RoleKind::Clawback,
+                })
+                .unwrap();
+            assert!(!asset.freeze_renounced());
+            assert!(!asset.clawback_renounced());
+
+            advance_blocks(3);
+            asset.execute_action(freeze).unwrap();
+            asset.execute_action(clawback).unwrap();
+            assert!(asset.freeze_renounced());
+            assert!(asset.clawback_renounced());
+        }
+
+        // Test if amounts are split into whole and fractional units
+        #[ink::test]
+        fn to_display_units_works() {
+            let with_decimals = |decimals| {
+                Subsa::new(AssetParams {
+                    decimals,
+                    ..default_params()
+                })
+            };
+
+            assert_eq!(with_decimals(0).to_display_units(1234), Ok((1234, 0)));
+            assert_eq!(
+                with_decimals(6).to_display_units(12_345_678),
+                Ok((12, 345_678))
+            );
+
+            // 10^38 is the largest power of ten fitting in a u128
+            assert_eq!(
+                with_decimals(38)
+                    .to_display_units(Balance::MAX)
+                    .map(|(whole, _)| whole),
+                Ok(3)
+            );
+            assert_eq!(with_decimals(39).to_display_units(1), Err(Error::Overflow));
+        }
+
+        // Test if allowances can be raised and lowered relative to their current value
+        #[ink::test]
+        fn increase_and_decrease_allowance_work() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            asset.increase_allowance(accounts.bob, 100).unwrap();
+            asset.increase_allowance(accounts.bob, 50).unwrap();
+            assert_eq!(asset.allowance(accounts.alice, accounts.bob), 150);
+            asset.decrease_allowance(accounts.bob, 30).unwrap();
+            assert_eq!(asset.allowance(accounts.alice, accounts.bob), 120);
+            assert_eq!(
+                asset.decrease_allowance(accounts.bob, 121),
+                Err(Error::InsufficientAllowance)
+            );
+
+            // the spender uses part of the allowance, the rest can still be lowered
+            set_caller(accounts.bob);
+            asset
+                .transfer_from(accounts.alice, accounts.charlie, 100)
+                .unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.decrease_allowance(accounts.bob, 30),
+                Err(Error::InsufficientAllowance)
+            );
+            asset.decrease_allowance(accounts.bob, 20).unwrap();
+            assert_eq!(asset.allowance(accounts.alice, accounts.bob), 0);
+
+            let approvals = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Approval(Approval { amount, .. })) => Some(amount),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(approvals, vec![100, 150, 120, 0]);
+        }
+
+        // Test if a freeze stores and emits its reason, and unfreezing clears it
+        #[ink::test]
+        fn freeze_reason_is_stored_and_cleared() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+
+            asset.freeze(accounts.bob, true, 7).unwrap();
+            assert_eq!(asset.freeze_reason(accounts.bob), 7);
+            asset.freeze(accounts.bob, false, 7).unwrap();
+            assert_eq!(asset.freeze_reason(accounts.bob), 0);
+
+            let reasons = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Freeze(Freeze { reason, .. })) => Some(reason),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(reasons, vec![0, 0, 7, 0]);
+        }
+
+        // Test if the sum of balances matches the issued supply after mints, transfers and burns
+        #[ink::test]
+        fn check_invariant_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.burn(100).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.assign(accounts.charlie, 50).unwrap();
+            asset.mint(accounts.bob, 80).unwrap();
+            set_caller(accounts.bob);
+            asset.burn(30).unwrap();
+            assert!(asset.check_invariant());
+            assert_eq!(asset.verify_invariants(), Ok(()));
+
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+            asset.pull(accounts.alice).unwrap();
+            assert!(asset.check_invariant());
+
+            // corrupt the running sum of balances
+            asset.sum_balances += 1;
+            assert!(!asset.check_invariant());
+            assert_eq!(asset.verify_invariants(), Err(Error::InvariantViolated));
+        }
+
+        // Test if transfers can't leave dust balances below the minimum balance
+        #[ink::test]
+        fn min_balance_prevents_dust() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                min_balance: 100,
+                ..default_params()
+            });
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            assert_eq!(asset.min_balance(), 100);
+
+            // above the minimum on both sides
+            asset.transfer(accounts.bob, 300).unwrap();
+            // receiver credited below the minimum
+            assert_eq!(
+                asset.transfer(accounts.charlie, 50),
+                Err(Error::BelowMinimumBalance)
+            );
+
+            set_caller(accounts.bob);
+            // sender left below the minimum
+            assert_eq!(
+                asset.transfer(accounts.charlie, 250),
+                Err(Error::BelowMinimumBalance)
+            );
+            // full drain to zero
+            asset.transfer(accounts.charlie, 300).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(300));
+        }
+
+        // Test if the version matches the constant and features follow the configuration
+        #[ink::test]
+        fn version_and_supports_feature_work() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                allow_escrow: true,
+                names_mutable: true,
+                ..default_params()
+            });
+            assert_eq!(asset.version(), CONTRACT_VERSION);
+            assert!(asset.supports_feature(FEATURE_ESCROW));
+            assert!(asset.supports_feature(FEATURE_RENAMING));
+            assert!(!asset.supports_feature(FEATURE_REGISTRY));
+            assert!(!asset.supports_feature(FEATURE_MULTI_APPROVAL));
+            assert!(!asset.supports_feature(FEATURE_KYC));
+            assert!(!asset.supports_feature(u32::MAX));
+
+            assert!(!asset.supports_feature(FEATURE_FEES));
+            asset.set_transfer_fee(50).unwrap();
+            assert!(asset.supports_feature(FEATURE_FEES));
+
+            assert!(!asset.supports_feature(FEATURE_SNAPSHOTS));
+            asset.take_snapshot().unwrap();
+            assert!(asset.supports_feature(FEATURE_SNAPSHOTS));
+        }
+
+        // Test if freeze_many freezes a list of accounts and skips those already frozen
+        #[ink::test]
+        fn freeze_many_works() {
+            let accounts = default_accounts();
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            for holder in [accounts.bob, accounts.charlie, accounts.django] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            asset.freeze(accounts.charlie, false, 0).unwrap();
+            let before = ink_env::test::recorded_events().count();
+
+            // django is still frozen by default and is skipped
+            asset
+                .freeze_many(
+                    ink_prelude::vec![accounts.bob, accounts.charlie, accounts.django],
+                    true,
+                )
+                .unwrap();
+            assert!(asset.is_frozen(accounts.bob));
+            assert!(asset.is_frozen(accounts.charlie));
+            assert!(asset.is_frozen(accounts.django));
+            assert_eq!(ink_env::test::recorded_events().count(), before + 2);
+
+            asset
+                .freeze_many(ink_prelude::vec![accounts.bob, accounts.django], false)
+                .unwrap();
+            assert!(!asset.is_frozen(accounts.bob));
+            assert!(asset.is_frozen(accounts.charlie));
+            assert!(!asset.is_frozen(accounts.django));
+
+            assert_eq!(
+                asset.freeze_many(ink_prelude::vec![accounts.alice; 129], true),
+                Err(Error::BatchTooLarge)
+            );
+        }
+
+        // Test if only the freeze address can freeze many accounts
+        #[ink::test]
+        fn freeze_many_requires_freeze_id() {
+            let accounts = default_accounts();
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.freeze_many(ink_prelude::vec![accounts.charlie], true),
+                Err(Error::NotFreezeId)
+            );
+        }
+
+        // Test if the manager can prune an old opt-in without a balance
+        #[ink::test]
+        fn prune_opt_in_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert_eq!(asset.opted_in_at(accounts.bob), Some(0));
+            assert_eq!(asset.opted_in_count(), 2);
+
+            // two blocks of 6 milliseconds each
+            advance_blocks(2);
+            assert_eq!(
+                asset.prune_opt_in(accounts.bob, 10),
+                Err(Error::NotManagerId)
+            );
+            set_caller(accounts.alice);
+            asset.prune_opt_in(accounts.bob, 10).unwrap();
+            assert!(!asset.is_opted_in(accounts.bob));
+            assert_eq!(asset.opted_in_at(accounts.bob), None);
+            assert_eq!(asset.opted_in_count(), 1);
+
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as Decode>::decode(&mut &last.data[..]),
+                Ok(Event::OptOut(OptOut { account, .. })) if account == accounts.bob
+            ));
+        }
+
+        // Test if recent opt-ins and accounts holding tokens can't be pruned
+        #[ink::test]
+        fn prune_opt_in_rejects_recent_and_funded() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.charlie, 100).unwrap();
+            advance_blocks(2);
+
+            assert_eq!(
+                asset.prune_opt_in(accounts.bob, 12),
+                Err(Error::OptInTooRecent)
+            );
+            assert_eq!(
+                asset.prune_opt_in(accounts.charlie, 10),
+                Err(Error::NonZeroBalance)
+            );
+            assert_eq!(
+                asset.prune_opt_in(accounts.django, 10),
+                Err(Error::NotOptedIn)
+            );
+            assert!(asset.is_opted_in(accounts.bob));
+            assert!(asset.is_opted_in(accounts.charlie));
+        }
+
+        // Test if config reflects the values set through each setter
+        #[ink::test]
+        fn config_reflects_setters() {
+            let mut asset = create_asset();
+            assert_eq!(
+                asset.config(),
+                ContractConfig {
+                    paused: false,
+                    transfer_fee_bps: 0,
+                    min_reserve: 0,
+                    max_holders: None,
+                    strict_clawback: false,
+                    supply_locked: false,
+                    emergency_exit_enabled: false,
+                    large_transfer_threshold: None,
+                    finality_delay: 0,
+                    transfer_cooldown: 0,
+                }
+            );
+
+            asset.pause().unwrap();
+            asset.set_transfer_fee(25).unwrap();
+            asset.set_min_reserve(100).unwrap();
+            asset.set_strict_clawback(true).unwrap();
+            asset.lock_supply().unwrap();
+            asset.set_emergency_exit(true).unwrap();
+            asset.set_large_transfer_threshold(Some(500), 10).unwrap();
+            asset.set_finality_delay(3).unwrap();
+            asset.set_transfer_cooldown(60).unwrap();
+            assert_eq!(
+                asset.config(),
+                ContractConfig {
+                    paused: true,
+                    transfer_fee_bps: 25,
+                    min_reserve: 100,
+                    max_holders: None,
+                    strict_clawback: true,
+                    supply_locked: true,
+                    emergency_exit_enabled: true,
+                    large_transfer_threshold: Some(500),
+                    finality_delay: 3,
+                    transfer_cooldown: 60,
+                }
+            );
+        }
+
+        // Test if the transfer hook is called with the transfer arguments
+        #[ink::test]
+        fn transfer_hook_is_called() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            assert_eq!(
+                asset.set_transfer_hook(Some(accounts.eve), true),
+                Err(Error::NotManagerId)
+            );
+            set_caller(accounts.alice);
+            asset.set_transfer_hook(Some(accounts.eve), true).unwrap();
+            assert_eq!(asset.transfer_hook(), Some(accounts.eve));
+            assert!(asset.hook_must_succeed());
+
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.approve(accounts.charlie, 100).unwrap();
+            set_caller(accounts.charlie);
+            asset
+                .transfer_from(accounts.alice, accounts.charlie, 100)
+                .unwrap();
+            // failed transfers don't reach the hook
+            assert_eq!(
+                asset.transfer(accounts.bob, 1000),
+                Err(Error::NotEnoughBalance)
+            );
+
+            HOOK_CALLS.with(|calls| {
+                assert_eq!(
+                    *calls.borrow(),
+                    ink_prelude::vec![
+                        (accounts.eve, accounts.alice, accounts.bob, 300),
+                        (accounts.eve, accounts.alice, accounts.charlie, 100),
+                    ]
+                )
+            });
+        }
+
+        // Test if an optional hook rejecting a transfer is ignored
+        #[ink::test]
+        fn optional_transfer_hook_failure_is_ignored() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.set_transfer_hook(Some(accounts.eve), false).unwrap();
+            HOOK_ACCEPTS.with(|accepts| accepts.set(false));
+
+            asset.transfer(accounts.bob, 300).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+        }
+
+        // Test if a mandatory hook rejecting a transfer reverts it
+        #[ink::test]
+        #[should_panic(expected = "transfer hook failed")]
+        fn mandatory_transfer_hook_failure_reverts() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.set_transfer_hook(Some(accounts.eve), true).unwrap();
+            HOOK_ACCEPTS.with(|accepts| accepts.set(false));
+
+            let _ = asset.transfer(accounts.bob, 300);
+        }
+
+        // Test if an account receiving and then sending within a chain of transfers
+        // ends with exact balances
+        #[ink::test]
+        fn chained_transfers_keep_exact_balances() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 300).unwrap();
+            set_caller(accounts.charlie);
+            asset.transfer(accounts.bob, 120).unwrap();
+            set_caller(accounts.bob);
+            asset
+                .batch_transfer(ink_prelude::vec![
+                    (accounts.charlie, 70),
+                    (accounts.alice, 50)
+                ])
+                .unwrap();
+
+            assert_eq!(asset.balance_of(accounts.alice), Ok(750));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(250));
+            assert!(asset.check_invariant());
+        }
+
+        // Test if a batch sent back to the sender leaves its balance exact
+        #[ink::test]
+        fn batch_transfer_to_self_keeps_exact_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset
+                .batch_transfer(ink_prelude::vec![
+                    (accounts.bob, 400),
+                    (accounts.alice, 100)
+                ])
+                .unwrap();
+            set_caller(accounts.bob);
+            asset.transfer(accounts.alice, 150).unwrap();
+
+            assert_eq!(asset.balance_of(accounts.alice), Ok(750));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(250));
+            assert!(asset.check_invariant());
+        }
+
+        // Test if the zero address can't receive tokens or be approved
+        #[ink::test]
+        fn zero_address_is_rejected() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            let zero = AccountId::from([0x0; 32]);
+
+            assert_eq!(asset.transfer(zero, 100), Err(Error::ZeroAddress));
+            assert_eq!(asset.approve(zero, 100), Err(Error::ZeroAddress));
+            assert_eq!(asset.increase_allowance(zero, 100), Err(Error::ZeroAddress));
+            asset.approve(accounts.bob, 100).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.transfer_from(accounts.alice, zero, 100),
+                Err(Error::ZeroAddress)
+            );
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+        }
+
+        // Test if minting to the zero address is rejected, while mints are still sent from it
+        #[ink::test]
+        fn mint_keeps_zero_sender_convention() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(
+                asset.mint(AccountId::from([0x0; 32]), 50),
+                Err(Error::ZeroAddress)
+            );
+
+            asset.mint(accounts.alice, 50).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1050));
+            assert_eq!(
+                recorded_transfers().pop(),
+                Some((AccountId::from([0x0; 32]), accounts.alice, 50))
+            );
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as Decode>::decode(&mut &last.data[..]),
+                Ok(Event::Mint(Mint { to, amount: 50, .. })) if to == accounts.alice
+            ));
+        }
+
+        // Test if account_state bundles the state of an account
+        #[ink::test]
+        fn account_state_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(
+                asset.account_state(accounts.bob),
+                AccountState {
+                    balance: 0,
+                    opted_in: false,
+                    frozen: false,
+                    locked: 0,
+                    pending: 0,
+                }
+            );
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 500).unwrap();
+            asset.lock_until(accounts.bob, 200, 12).unwrap();
+            assert_eq!(
+                asset.account_state(accounts.bob),
+                AccountState {
+                    balance: 500,
+                    opted_in: true,
+                    frozen: false,
+                    locked: 200,
+                    pending: 0,
+                }
+            );
+        }
+
+        // Test if an exempt account keeps receiving while frozen, until the exemption is revoked
+        #[ink::test]
+        fn freeze_exempt_account_can_receive_while_frozen() {
+            let accounts = default_accounts();
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            assert_eq!(
+                asset.set_freeze_exempt(accounts.bob, true),
+                Err(Error::NotFreezeId)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.batch_transfer(ink_prelude::vec![(accounts.bob, 100)]),
+                Err(Error::FrozenAccount)
+            );
+
+            asset.set_freeze_exempt(accounts.bob, true).unwrap();
+            assert!(asset.is_freeze_exempt(accounts.bob));
+            assert!(asset.is_frozen(accounts.bob));
+            asset
+                .batch_transfer(ink_prelude::vec![(accounts.bob, 100)])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+            // charlie is frozen and not exempt
+            assert_eq!(
+                asset.batch_transfer(ink_prelude::vec![(accounts.charlie, 100)]),
+                Err(Error::FrozenAccount)
+            );
+
+            asset.set_freeze_exempt(accounts.bob, false).unwrap();
+            assert!(!asset.is_freeze_exempt(accounts.bob));
+            assert_eq!(
+                asset.batch_transfer(ink_prelude::vec![(accounts.bob, 100)]),
+                Err(Error::FrozenAccount)
+            );
+
+            // exemptions can't change once freezing is renounced, or on a non-freezable asset
+            asset.renounce_freeze().unwrap();
+            assert_eq!(
+                asset.set_freeze_exempt(accounts.bob, true),
+                Err(Error::FreezeRenounced)
+            );
+            let mut asset = Subsa::new(AssetParams {
+                freezable: false,
+                ..default_params()
+            });
+            assert_eq!(
+                asset.set_freeze_exempt(accounts.bob, true),
+                Err(Error::NotFreezable)
+            );
+        }
+
+        // Test if a configured asset ID flows through to emitted events
+        #[ink::test]
+        fn asset_id_flows_to_events() {
+            let accounts = default_accounts();
+            let asset = create_asset();
+            assert_eq!(
+                asset.asset_id(),
+                ink_env::account_id::<ink_env::DefaultEnvironment>()
+            );
+
+            let mut asset = Subsa::new(AssetParams {
+                asset_id: Some(AccountId::from([0x7; 32])),
+                ..default_params()
+            });
+            assert_eq!(asset.asset_id(), AccountId::from([0x7; 32]));
+            let first = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as Decode>::decode(&mut &first.data[..]),
+                Ok(Event::Creation(Creation { asset_id, .. })) if asset_id == AccountId::from([0x7; 32])
+            ));
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as Decode>::decode(&mut &last.data[..]),
+                Ok(Event::Transfer(Transfer { asset_id, .. })) if asset_id == AccountId::from([0x7; 32])
+            ));
+        }
+
+        /// Creates an asset that escrows transfers to accounts that haven't opted in.
+        fn create_escrow_asset() -> Subsa {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            Subsa::new(AssetParams {
+                allow_escrow: true,
+                ..default_params()
+            })
+        }
+
+        // Test if the manager can reclaim an escrowed balance after the deadline only
+        #[ink::test]
+        fn reclaim_pending_after_deadline() {
+            let accounts = default_accounts();
+            let mut asset = create_escrow_asset();
+            asset.set_escrow_deadline(10).unwrap();
+            asset.transfer(accounts.bob, 300).unwrap();
+            assert_eq!(asset.balance_of(accounts.alice), Ok(700));
+
+            // two blocks of 6 milliseconds each
+            advance_blocks(1);
+            assert_eq!(
+                asset.reclaim_pending(accounts.bob),
+                Err(Error::EscrowNotExpired)
+            );
+            advance_blocks(1);
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.reclaim_pending(accounts.bob),
+                Err(Error::NotManagerId)
+            );
+            set_caller(accounts.alice);
+            asset.reclaim_pending(accounts.bob).unwrap();
+            assert_eq!(asset.pending_balance(accounts.bob), 0);
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+            assert!(asset.check_invariant());
+        }
+
+        // Test if a claimed escrow balance can't be reclaimed
+        #[ink::test]
+        fn reclaim_pending_rejects_claimed_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_escrow_asset();
+            asset.transfer(accounts.bob, 300).unwrap();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            asset.claim().unwrap();
+
+            advance_blocks(2);
+            set_caller(accounts.alice);
+            assert_eq!(asset.reclaim_pending(accounts.bob), Err(Error::ZeroAmount));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+        }
+
+        // Test if the frozen status resolves the explicit status, then the default override,
+        // then default_frozen
+        #[ink::test]
+        fn freeze_default_override_precedence() {
+            let accounts = default_accounts();
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+
+            // global default
+            assert!(asset.is_frozen(accounts.charlie));
+
+            // override over global default
+            asset
+                .set_freeze_default_override(accounts.charlie, Some(false))
+                .unwrap();
+            assert_eq!(asset.freeze_default_override(accounts.charlie), Some(false));
+            assert!(!asset.is_frozen(accounts.charlie));
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+            assert!(!asset.is_frozen(accounts.charlie));
+
+            // explicit status over override
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            asset
+                .set_freeze_default_override(accounts.bob, Some(true))
+                .unwrap();
+            assert!(!asset.is_frozen(accounts.bob));
+
+            // removing the override falls back to the global default
+            asset
+                .set_freeze_default_override(accounts.charlie, None)
+                .unwrap();
+            assert!(asset.is_frozen(accounts.charlie));
+        }
+
+        // Test if an override set after opt-in applies to a holder that was frozen by default
+        #[ink::test]
+        fn freeze_default_override_applies_after_opt_in() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert!(asset.is_frozen(accounts.bob));
+
+            set_caller(accounts.alice);
+            asset
+                .set_freeze_default_override(accounts.bob, Some(false))
+                .unwrap();
+            assert!(!asset.is_frozen(accounts.bob));
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if only the freeze address can set default overrides, and the frozen
+        // balance follows them
+        #[ink::test]
+        fn freeze_default_override_tracks_frozen_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.set_freeze_default_override(accounts.bob, Some(true)),
+                Err(Error::NotFreezeId)
+            );
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            asset
+                .set_freeze_default_override(accounts.bob, Some(true))
+                .unwrap();
+            assert!(asset.is_frozen(accounts.bob));
+            assert_eq!(asset.total_frozen, 300);
+            asset
+                .set_freeze_default_override(accounts.bob, Some(false))
+                .unwrap();
+            assert_eq!(asset.total_frozen, 0);
+        }
+
+        // Test if batch messages accept exactly MAX_BATCH entries and reject one more
+        #[ink::test]
+        fn batch_messages_are_bounded() {
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            // start past the default accounts
+            let holders = (0x10..0x10 + MAX_BATCH as u8)
+                .map(|index| AccountId::from([index; 32]))
+                .collect::<Vec<_>>();
+            for holder in &holders {
+                set_caller(*holder);
+                asset.opt_in().unwrap();
+            }
+            let mut too_many = holders.clone();
+            too_many.push(AccountId::from([0xff; 32]));
+
+            set_caller(default_accounts().alice);
+            asset.freeze_many(holders.clone(), false).unwrap();
+            assert_eq!(
+                asset.freeze_many(too_many.clone(), false),
+                Err(Error::BatchTooLarge)
+            );
+
+            let transfers = |accounts: &Vec<AccountId>| {
+                accounts
+                    .iter()
+                    .map(|account| (*account, 1))
+                    .collect::<Vec<_>>()
+            };
+            asset.batch_transfer(transfers(&holders)).unwrap();
+            assert_eq!(
+                asset.batch_transfer(transfers(&too_many)),
+                Err(Error::BatchTooLarge)
+            );
+
+            asset.distribute(128, holders.clone()).unwrap();
+            assert_eq!(
+                asset.distribute(129, too_many.clone()),
+                Err(Error::BatchTooLarge)
+            );
+
+            assert_eq!(asset.balances_of(holders.clone()), Ok(vec![2; MAX_BATCH]));
+            assert_eq!(asset.balances_of(too_many), Err(Error::BatchTooLarge));
+        }
+
+        // Test if can_transfer returns the same error as the transfer it checks
+        #[ink::test]
+        fn can_transfer_matches_transfer() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                min_balance: 10,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.set_large_transfer_threshold(Some(900), 10).unwrap();
+
+            let cases = [
+                (accounts.alice, accounts.bob, 5),
+                (accounts.alice, accounts.bob, 100),
+                (accounts.alice, accounts.charlie, 100),
+                (accounts.alice, accounts.bob, 950),
+                (accounts.bob, accounts.alice, 200),
+                (accounts.alice, AccountId::from([0x0; 32]), 100),
+            ];
+            for (from, to, amount) in cases {
+                let expected = asset.can_transfer(from, to, amount);
+                set_caller(from);
+                assert_eq!(asset.transfer(to, amount), expected);
+            }
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+
+            set_caller(accounts.alice);
+            asset.pause().unwrap();
+            assert_eq!(
+                asset.can_transfer(accounts.alice, accounts.bob, 100),
+                Err(Error::ContractPaused)
+            );
+            assert_eq!(
+                asset.transfer(accounts.bob, 100),
+                Err(Error::ContractPaused)
+            );
+        }
+
+        // Test if received totals accumulate and don't decrease when sending out
+        #[ink::test]
+        fn received_of_accumulates() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.bob, 50).unwrap();
+            asset.approve(accounts.charlie, 30).unwrap();
+            set_caller(accounts.charlie);
+            asset
+                .transfer_from(accounts.alice, accounts.bob, 30)
+                .unwrap();
+            set_caller(accounts.alice);
+            asset.release(accounts.bob, 20).unwrap();
+            assert_eq!(asset.received_of(accounts.bob), 200);
+
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 120).unwrap();
+            assert_eq!(asset.received_of(accounts.bob), 200);
+            assert_eq!(asset.received_of(accounts.charlie), 120);
+            assert_eq!(asset.received_of(accounts.alice), 0);
+
+            set_caller(accounts.alice);
+            asset.mint(accounts.bob, 10).unwrap();
+            assert_eq!(asset.received_of(accounts.bob), 210);
+        }
+
+        // Test if transfers within the cooldown are rejected, and allowed after it
+        #[ink::test]
+        fn transfer_cooldown_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert_eq!(asset.set_transfer_cooldown(10), Err(Error::NotManagerId));
+            set_caller(accounts.alice);
+            asset.set_transfer_cooldown(10).unwrap();
+
+            asset.transfer(accounts.bob, 100).unwrap();
+            // two blocks of 6 milliseconds each
+            advance_blocks(1);
+            assert_eq!(
+                asset.transfer(accounts.bob, 100),
+                Err(Error::CooldownActive)
+            );
+            assert_eq!(
+                asset.can_transfer(accounts.alice, accounts.bob, 100),
+                Err(Error::CooldownActive)
+            );
+            // other accounts have their own cooldown
+            set_caller(accounts.bob);
+            asset.transfer(accounts.alice, 50).unwrap();
+
+            advance_blocks(1);
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(150));
+        }
+
+        // Test if batch transfers, transfer_from, confirmed transfers and redeems share the
+        // cooldown
+        #[ink::test]
+        fn transfer_cooldown_covers_every_send_path() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.set_transfer_cooldown(10).unwrap();
+            asset.set_large_transfer_threshold(Some(100), 2).unwrap();
+            asset.approve(accounts.alice, 100).unwrap();
+
+            asset.batch_transfer(vec![(accounts.bob, 50)]).unwrap();
+            assert_eq!(
+                asset.transfer_from(accounts.alice, accounts.bob, 50),
+                Err(Error::CooldownActive)
+            );
+            asset.commit_large_transfer(accounts.bob, 200).unwrap();
+            assert_eq!(asset.confirm_large_transfer(), Err(Error::CooldownActive));
+
+            advance_blocks(2);
+            asset.confirm_large_transfer().unwrap();
+            assert_eq!(
+                asset.batch_transfer(vec![(accounts.bob, 50)]),
+                Err(Error::CooldownActive)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(250));
+
+            set_caller(accounts.bob);
+            asset.redeem(10).unwrap();
+            assert_eq!(asset.redeem(10), Err(Error::CooldownActive));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(240));
+        }
+
+        // Test if a zero cooldown and freeze exempt accounts always transfer
+        #[ink::test]
+        fn transfer_cooldown_disabled_or_exempt() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            asset.set_transfer_cooldown(10).unwrap();
+            asset.set_freeze_exempt(accounts.alice, true).unwrap();
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(400));
+        }
+
+        // Test if holders can redeem tokens back to the reserve, and the redeemed supply
+        // accumulates
+        #[ink::test]
+        fn redeem_returns_tokens_to_reserve() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            set_caller(accounts.bob);
+            asset.redeem(100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(800));
+            assert_eq!(asset.redeemed_supply(), 100);
+
+            asset.redeem(50).unwrap();
+            assert_eq!(asset.redeemed_supply(), 150);
+            assert_eq!(asset.redeem(0), Err(Error::ZeroAmount));
+            assert_eq!(asset.redeem(500), Err(Error::NotEnoughBalance));
+            assert_eq!(asset.redeemed_supply(), 150);
+            assert!(asset.check_invariant());
+        }
+
+        // Test if frozen holders and holders that haven't opted in can't redeem
+        #[ink::test]
+        fn redeem_rejects_frozen_account() {
+            let accounts = default_accounts();
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.freeze(accounts.bob, true, 0).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.redeem(100), Err(Error::FrozenAccount));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+            set_caller(accounts.charlie);
+            assert_eq!(asset.redeem(100), Err(Error::NotOptedIn));
+            assert_eq!(asset.redeemed_supply(), 0);
+        }
+
+        // Test if the clawback address can revoke part of a balance, and the revoke event
+        // names the revocation target
+        #[ink::test]
+        fn revoke_asset_partial_amount() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.revoke_asset(accounts.bob, accounts.bob, 40),
+                Err(Error::NotClawbackId)
+            );
+            set_caller(accounts.alice);
+            asset
+                .revoke_asset(accounts.alice, accounts.bob, 40)
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(60));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(940));
+
+            let revoked_from = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Revoke(Revoke { from, amount, .. })) => Some((from, amount)),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(revoked_from, vec![(accounts.bob, Some(40))]);
+        }
+
+        // Test if the asset can only be destroyed once every token is back with the manager
+        // or the reserve
+        #[ink::test]
+        fn destroy_asset_requires_returned_supply() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset
+                .modify_asset(None, Some(accounts.charlie), None, None)
+                .unwrap();
+            asset.transfer(accounts.bob, 300).unwrap();
+            asset.burn(100).unwrap();
+            assert!(!asset.is_destroyable());
+            assert_eq!(
+                asset.destroy_asset(),
+                Err(Error::NotAllAssetsOwnedByManager)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(asset.destroy_asset(), Err(Error::NotManagerId));
+
+            // tokens redeemed to the reserve count as returned
+            asset.redeem(300).unwrap();
+            assert!(asset.is_destroyable());
+
+            set_caller(accounts.alice);
+            let contract = ink_env::test::callee::<ink_env::DefaultEnvironment>();
+            let deposit =
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(contract)
+                    .unwrap();
+            ink_env::test::assert_contract_termination::<ink_env::DefaultEnvironment, _>(
+                move || {
+                    let _ = asset.destroy_asset();
+                },
+                accounts.alice,
+                deposit,
+            );
+        }
+
+        // Test if the initial supply is credited to a supplied reserve, which is opted in and
+        // can transfer right away
+        #[ink::test]
+        fn constructor_allocates_supply_to_reserve() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                reserve: Some(accounts.charlie),
+                freeze: None,
+                clawback: None,
+                ..default_params()
+            });
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(1000));
+            assert!(asset.is_opted_in(accounts.charlie));
+            assert!(!asset.is_opted_in(accounts.alice));
+
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.charlie);
+            asset.transfer(accounts.bob, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
+        }
+
+        // Test if the issued supply follows mints and burns, and includes the reserve balance
+        #[ink::test]
+        fn total_supply_tracks_issued_supply() {
+            let mut asset = create_asset();
+            assert_eq!(asset.total_supply(), 1000);
+            assert_eq!(asset.circulating_supply(), 0);
+            asset.mint(default_accounts().alice, 100).unwrap();
+            assert_eq!(asset.total_supply(), 1100);
+            // tokens minted to the reserve don't circulate
+            assert_eq!(asset.circulating_supply(), 0);
+            asset.burn(300).unwrap();
+            assert_eq!(asset.total_supply(), 800);
+            assert_eq!(asset.total(), 1000);
+        }
+
+        // Test if the PSP22 messages move balances and map errors to the PSP22 errors
+        #[ink::test]
+        fn psp22_messages_work() {
+            use psp22::{PSP22Error, PSP22};
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+
+            assert_eq!(PSP22::total_supply(&asset), 1000);
+            assert_eq!(PSP22::balance_of(&asset, accounts.charlie), 0);
+            PSP22::transfer(&mut asset, accounts.bob, 100, Vec::new()).unwrap();
+            assert_eq!(PSP22::balance_of(&asset, accounts.bob), 100);
+            assert_eq!(
+                PSP22::transfer(&mut asset, accounts.charlie, 100, Vec::new()),
+                Err(PSP22Error::Custom("NotOptedIn".into()))
+            );
+            assert_eq!(
+                PSP22::transfer(&mut asset, AccountId::from([0x0; 32]), 100, Vec::new()),
+                Err(PSP22Error::ZeroRecipientAddress)
+            );
+
+            PSP22::approve(&mut asset, accounts.charlie, 50).unwrap();
+            PSP22::increase_allowance(&mut asset, accounts.charlie, 20).unwrap();
+            PSP22::decrease_allowance(&mut asset, accounts.charlie, 10).unwrap();
+            assert_eq!(
+                PSP22::allowance(&asset, accounts.alice, accounts.charlie),
+                60
+            );
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                PSP22::transfer_from(&mut asset, accounts.alice, accounts.bob, 61, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                PSP22::transfer(&mut asset, accounts.bob, 10, Vec::new()),
+                Err(PSP22Error::InsufficientBalance)
+            );
+            PSP22::transfer_from(&mut asset, accounts.alice, accounts.bob, 60, Vec::new()).unwrap();
+            assert_eq!(PSP22::balance_of(&asset, accounts.bob), 160);
+            assert_eq!(
+                PSP22::allowance(&asset, accounts.alice, accounts.charlie),
+                0
+            );
+        }
+
+        // Test if the PSP22 metadata follows the asset name, unit name and decimals
+        #[ink::test]
+        fn psp22_metadata_works() {
+            use psp22::PSP22Metadata;
+            let asset = create_asset();
+            assert_eq!(asset.token_name(), Some("Test subsa".into()));
+            assert_eq!(asset.token_symbol(), Some("TSSA".into()));
+            assert_eq!(asset.token_decimals(), 10);
+
+            let asset = Subsa::new(AssetParams {
+                decimals: 300,
+                ..default_params()
+            });
+            assert_eq!(asset.token_decimals(), u8::MAX);
+        }
+
+        // Test if the reserve can burn approved tokens from a holder, which lowers the
+        // issued supply
+        #[ink::test]
+        fn burn_from_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.burn_from(accounts.alice, 100),
+                Err(Error::NotReserveId)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.burn_from(accounts.bob, 100),
+                Err(Error::InsufficientAllowance)
+            );
+
+            set_caller(accounts.bob);
+            asset.approve(accounts.alice, 400).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.burn_from(accounts.bob, 301),
+                Err(Error::NotEnoughBalance)
+            );
+            asset.burn_from(accounts.bob, 100).unwrap();
+            assert_eq!(asset.allowance(accounts.bob, accounts.alice), 300);
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+            assert_eq!(asset.total_supply(), 900);
+            assert_eq!(
+                recorded_transfers().last(),
+                Some(&(accounts.bob, AccountId::from([0x0; 32]), 100))
+            );
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as Decode>::decode(&mut &last.data[..]),
+                Ok(Event::Burn(Burn { from, amount: 100, .. })) if from == accounts.bob
+            ));
+            assert!(asset.check_invariant());
+
+            // frozen holdings can't be burned away from the clawback
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            assert_eq!(
+                asset.burn_from(accounts.bob, 100),
+                Err(Error::FrozenAccount)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(asset.burn(100), Err(Error::FrozenAccount));
+            assert_eq!(asset.balance_of(accounts.bob), Ok(200));
+        }
+
+        // Test if a burn can't leave a dust balance behind
+        #[ink::test]
+        fn burn_respects_minimum_balance() {
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                min_balance: 50,
+                ..default_params()
+            });
+            assert_eq!(asset.burn(960), Err(Error::BelowMinimumBalance));
+            asset.burn(950).unwrap();
+            asset.burn(50).unwrap();
+            assert_eq!(asset.total_supply(), 0);
+        }
+
+        // Test if a batch leaving a receiver with a dust balance is rejected as a whole
+        #[ink::test]
+        fn batch_transfer_respects_minimum_balance() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                min_balance: 50,
+                ..default_params()
+            });
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+
+            assert_eq!(
+                asset.batch_transfer(ink_prelude::vec![
+                    (accounts.bob, 100),
+                    (accounts.charlie, 10)
+                ]),
+                Err(Error::BelowMinimumBalance)
+            );
+            assert_eq!(
+                asset.batch_transfer(ink_prelude::vec![(accounts.bob, 980)]),
+                Err(Error::BelowMinimumBalance)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+
+            asset
+                .batch_transfer(ink_prelude::vec![
+                    (accounts.bob, 100),
+                    (accounts.charlie, 50)
+                ])
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(50));
+        }
+
+        // Test if opting out with a balance is rejected, so tokens can't be stranded
+        #[ink::test]
+        fn opt_out_requires_zero_balance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(asset.opt_out(), Err(Error::NonZeroBalance));
+            assert_eq!(asset.opt_out_to(accounts.bob), Err(Error::NonZeroBalance));
+            assert!(asset.is_opted_in(accounts.bob));
+
+            asset.transfer(accounts.alice, 100).unwrap();
+            asset.opt_out().unwrap();
+            assert!(!asset.is_opted_in(accounts.bob));
+        }
+
+        // Test if transfers from and to a frozen account are rejected, including transfers
+        // to self and through an allowance
+        #[ink::test]
+        fn transfer_rejects_frozen_accounts() {
+            let accounts = default_accounts();
+            set_caller(default_accounts().alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                ..default_params()
+            });
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
             }
+            set_caller(accounts.alice);
+            asset.freeze(accounts.bob, false, 0).unwrap();
+            asset.transfer(accounts.bob, 300).unwrap();
 
-            // emit destroy asset event
-            self.env().emit_event(Destruction {
-                asset_id: self.asset_id(),
-                destroyer: self.manager_id,
+            // to a frozen receiver
+            assert_eq!(
+                asset.transfer(accounts.charlie, 100),
+                Err(Error::FrozenAccount)
+            );
+            asset.freeze(accounts.charlie, false, 0).unwrap();
+            asset.freeze(accounts.bob, true, 0).unwrap();
+
+            // from a frozen sender
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.transfer(accounts.charlie, 100),
+                Err(Error::FrozenAccount)
+            );
+            assert_eq!(asset.transfer(accounts.bob, 100), Err(Error::FrozenAccount));
+            asset.approve(accounts.charlie, 100).unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(
+                asset.transfer_from(accounts.bob, accounts.charlie, 100),
+                Err(Error::FrozenAccount)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+
+            // freeze exempt accounts keep transferring
+            set_caller(accounts.alice);
+            asset.set_freeze_exempt(accounts.bob, true).unwrap();
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 100).unwrap();
+            assert_eq!(asset.balance_of(accounts.charlie), Ok(100));
+        }
+
+        // Test if accounts of a freezable asset can be frozen even if it isn't default frozen,
+        // and once created without a freeze address, as soon as one is set
+        #[ink::test]
+        fn freezable_is_independent_of_freeze_address() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                freeze: None,
+                ..default_params()
             });
+            assert!(asset.freezable());
+            assert!(!asset.default_frozen());
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert!(!asset.is_frozen(accounts.bob));
+            set_caller(accounts.alice);
+            assert_eq!(asset.freeze(accounts.bob, true, 0), Err(Error::NotFreezeId));
+            asset
+                .modify_asset(None, None, Some(accounts.alice), None)
+                .unwrap();
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            assert!(asset.is_frozen(accounts.bob));
+        }
 
-            // terminate contract
-            self.env().terminate_contract(self.manager_id);
+        // Test if a default frozen asset created as not freezable keeps its holders frozen,
+        // even with a freeze address
+        #[ink::test]
+        fn not_freezable_keeps_default_frozen_holders() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                default_frozen: true,
+                reserve: None,
+                freezable: false,
+                clawback: None,
+                ..default_params()
+            });
+            assert!(!asset.freezable());
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            assert!(asset.is_frozen(accounts.bob));
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.freeze(accounts.bob, false, 0),
+                Err(Error::NotFreezable)
+            );
+            assert_eq!(
+                asset.freeze_many(ink_prelude::vec![accounts.bob], false),
+                Err(Error::NotFreezable)
+            );
+            assert_eq!(
+                asset.set_freeze_default_override(accounts.bob, Some(false)),
+                Err(Error::NotFreezable)
+            );
         }
-    }
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
+        // Test if opt-ins and clawbacks are blocked while the asset is paused
+        #[ink::test]
+        fn pause_blocks_opt_ins_and_clawbacks() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.pause().unwrap();
 
-        /// Imports `ink_lang` so we can use `#[ink::test]`.
-        use ink_lang as ink;
+            set_caller(accounts.charlie);
+            assert_eq!(asset.opt_in(), Err(Error::ContractPaused));
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.force_opt_in(accounts.charlie),
+                Err(Error::ContractPaused)
+            );
+            assert_eq!(
+                asset.revoke_asset(accounts.alice, accounts.bob, 50),
+                Err(Error::ContractPaused)
+            );
+            assert_eq!(
+                asset.force_transfer(accounts.bob, accounts.alice, 50),
+                Err(Error::ContractPaused)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(100));
 
-        use scale::Decode;
+            asset.unpause().unwrap();
+            asset
+                .revoke_asset(accounts.alice, accounts.bob, 50)
+                .unwrap();
+            set_caller(accounts.charlie);
+            asset.opt_in().unwrap();
+        }
 
-        /// Test constructor and initial state
+        // Test if a new manager only takes over once it accepts, and the old manager stays
+        // active until then
         #[ink::test]
-        fn constructor_works() {
-            let asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                Some(AccountId::from([0x0; 32])),
-                Some(AccountId::from([0x0; 32])),
-                Some(AccountId::from([0x0; 32])),
-                Some(AccountId::from([0x0; 32])),
+        fn manager_handover_requires_acceptance() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(
+                asset.propose_manager(AccountId::from([0x0; 32])),
+                Err(Error::ZeroAddress)
             );
+            asset.propose_manager(accounts.django).unwrap();
+            // a new proposal replaces a typoed one
+            asset.propose_manager(accounts.bob).unwrap();
+            assert_eq!(asset.pending_manager(), Some(accounts.bob));
+            assert_eq!(asset.manager_id(), accounts.alice);
+            asset.set_min_reserve(1).unwrap();
 
-            assert_eq!(asset.asset_name(), "Test subsa");
-            assert_eq!(asset.unit_name(), "TSSA");
-            assert_eq!(asset.total(), 1000);
-            assert_eq!(asset.decimals(), 10);
-            assert_eq!(asset.default_frozen(), true);
-            assert_eq!(asset.url(), "www.test.com");
+            set_caller(accounts.django);
+            assert_eq!(asset.accept_manager(), Err(Error::NotManagerId));
+            set_caller(accounts.bob);
+            assert_eq!(asset.set_min_reserve(2), Err(Error::NotManagerId));
+            asset.accept_manager().unwrap();
+            assert_eq!(asset.manager_id(), accounts.bob);
+            assert_eq!(asset.pending_manager(), None);
+            asset.set_min_reserve(2).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(asset.set_min_reserve(3), Err(Error::NotManagerId));
+        }
+
+        // Test if modify_asset proposes a new manager instead of switching to it
+        #[ink::test]
+        fn modify_asset_proposes_new_manager() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset
+                .modify_asset(
+                    Some(accounts.bob),
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                )
+                .unwrap();
+            assert_eq!(asset.manager_id(), accounts.alice);
+            assert_eq!(asset.pending_manager(), Some(accounts.bob));
+
+            set_caller(accounts.bob);
+            asset.accept_manager().unwrap();
+            assert_eq!(asset.manager_id(), accounts.bob);
+
+            let handovers = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::ManagerProposed(ManagerProposed {
+                            manager_id,
+                            proposed,
+                            ..
+                        })) => Some((manager_id, proposed)),
+                        Ok(Event::ManagerChanged(ManagerChanged {
+                            previous,
+                            manager_id,
+                            ..
+                        })) => Some((previous, manager_id)),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(
+                handovers,
+                vec![
+                    (accounts.alice, accounts.bob),
+                    (accounts.alice, accounts.bob)
+                ]
+            );
+
+            // clearing the manager is immediate
+            asset
+                .modify_asset(Some(AccountId::from([0x0; 32])), None, None, None)
+                .unwrap();
             assert_eq!(asset.manager_id(), AccountId::from([0x0; 32]));
-            assert_eq!(asset.reserve_id(), AccountId::from([0x0; 32]));
-            assert_eq!(asset.freeze_id(), AccountId::from([0x0; 32]));
+        }
+
+        // Test if granted roles authorize their holders next to the authority address,
+        // until revoked or renounced
+        #[ink::test]
+        fn role_grants_authorize_accounts() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            assert_eq!(
+                asset.grant_role(RoleKind::Freeze, accounts.bob),
+                Err(Error::NotManagerId)
+            );
+
+            set_caller(accounts.alice);
+            asset.grant_role(RoleKind::Freeze, accounts.bob).unwrap();
+            asset.grant_role(RoleKind::Freeze, accounts.django).unwrap();
+            assert!(asset.has_role(RoleKind::Freeze, accounts.bob));
+            assert!(asset.has_role(RoleKind::Freeze, accounts.alice));
+            assert!(!asset.has_role(RoleKind::Clawback, accounts.bob));
+
+            set_caller(accounts.bob);
+            asset.freeze(accounts.charlie, true, 0).unwrap();
+            set_caller(accounts.django);
+            asset.freeze(accounts.charlie, false, 0).unwrap();
+            asset.renounce_role(RoleKind::Freeze).unwrap();
+            assert_eq!(
+                asset.freeze(accounts.charlie, true, 0),
+                Err(Error::NotFreezeId)
+            );
+
+            set_caller(accounts.alice);
+            asset.revoke_role(RoleKind::Freeze, accounts.bob).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.freeze(accounts.charlie, true, 0),
+                Err(Error::NotFreezeId)
+            );
+            assert!(!asset.has_role(RoleKind::Freeze, accounts.bob));
+        }
+
+        // Test if custom roles can be granted and checked, and emit role events
+        #[ink::test]
+        fn custom_roles_work() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            let auditor = RoleKind::Custom(7);
+            assert!(!asset.has_role(auditor, accounts.alice));
+            asset.grant_role(auditor, accounts.bob).unwrap();
+            // granting twice doesn't emit a second event
+            asset.grant_role(auditor, accounts.bob).unwrap();
+            assert!(asset.has_role(auditor, accounts.bob));
+            assert!(!asset.has_role(RoleKind::Custom(8), accounts.bob));
+            asset.revoke_role(auditor, accounts.bob).unwrap();
+            assert!(!asset.has_role(auditor, accounts.bob));
+
+            let role_events = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::RoleGranted(RoleGranted { role, account, .. })) => {
+                            Some((true, role, account))
+                        }
+                        Ok(Event::RoleRevoked(RoleRevoked { role, account, .. })) => {
+                            Some((false, role, account))
+                        }
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(
+                role_events,
+                vec![
+                    (true, auditor, accounts.bob),
+                    (false, auditor, accounts.bob)
+                ]
+            );
+        }
+
+        // Test if every clawback needs the approval of the co-managers, whatever its amount
+        #[ink::test]
+        fn multi_authority_revoke_executes_at_threshold() {
+            let accounts = default_accounts();
+            let mut asset = create_multi_authority_asset();
+            set_caller(accounts.django);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.django, 400).unwrap();
+
+            // a second clawback holder to reach the threshold with
+            let action_id = asset
+                .propose_action(PendingAction::GrantRole {
+                    role: RoleKind::Clawback,
+                    account: accounts.charlie,
+                })
+                .unwrap();
+            set_caller(accounts.bob);
+            asset.approve_action(action_id).unwrap();
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.revoke_asset(accounts.alice, accounts.django, 1),
+                Err(Error::ApprovalRequired)
+            );
+            assert_eq!(
+                asset.force_transfer(accounts.django, accounts.alice, 1),
+                Err(Error::ApprovalRequired)
+            );
+
+            let action_id = asset
+                .propose_action(PendingAction::Revoke {
+                    receiver: accounts.alice,
+                    target: accounts.django,
+                    amount: 300,
+                })
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.django), Ok(400));
+            // co-managers without the clawback role can't approve it
+            set_caller(accounts.bob);
+            assert_eq!(asset.approve_action(action_id), Err(Error::NotClawbackId));
+            set_caller(accounts.charlie);
+            asset.approve_action(action_id).unwrap();
+            assert_eq!(asset.balance_of(accounts.django), Ok(100));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(900));
+
+            set_caller(accounts.alice);
+            let action_id = asset
+                .propose_action(PendingAction::ForceTransfer {
+                    from: accounts.django,
+                    to: accounts.alice,
+                    amount: 100,
+                })
+                .unwrap();
+            assert_eq!(asset.balance_of(accounts.django), Ok(100));
+            set_caller(accounts.charlie);
+            asset.approve_action(action_id).unwrap();
+            assert_eq!(asset.balance_of(accounts.django), Ok(0));
+            assert_eq!(asset.balance_of(accounts.alice), Ok(1000));
+        }
+
+        // Test if the manager can't seize holdings by proposing a clawback without the role
+        #[ink::test]
+        fn clawback_proposal_requires_clawback_authority() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                clawback: Some(accounts.eve),
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            let revoke = PendingAction::Revoke {
+                receiver: accounts.alice,
+                target: accounts.bob,
+                amount: 300,
+            };
+            assert_eq!(asset.propose_action(revoke), Err(Error::NotClawbackId));
+            assert_eq!(
+                asset.propose_action(PendingAction::ForceTransfer {
+                    from: accounts.bob,
+                    to: accounts.alice,
+                    amount: 300,
+                }),
+                Err(Error::NotClawbackId)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+
+            set_caller(accounts.eve);
+            asset.propose_action(revoke).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(0));
+
+            set_caller(accounts.alice);
+            asset.renounce_clawback().unwrap();
+            set_caller(accounts.eve);
+            assert_eq!(asset.propose_action(revoke), Err(Error::ClawbackRenounced));
+        }
+
+        // Test if an asset created without a clawback address can't claw back through a
+        // proposal
+        #[ink::test]
+        fn clawback_proposal_rejected_without_clawback_address() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                clawback: None,
+                ..default_params()
+            });
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 300).unwrap();
+
+            assert_eq!(
+                asset.propose_action(PendingAction::Revoke {
+                    receiver: accounts.alice,
+                    target: accounts.bob,
+                    amount: 300,
+                }),
+                Err(Error::NotClawbackId)
+            );
+            assert_eq!(asset.balance_of(accounts.bob), Ok(300));
+        }
+
+        // Test if a timelocked modification only executes once the delay has passed since
+        // its proposal
+        #[ink::test]
+        fn timelocked_modify_waits_for_delay() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_reconfig_delay(3).unwrap();
+            assert_eq!(asset.reconfig_delay(), 3);
+            assert_eq!(
+                asset.modify_asset(
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    Some(accounts.alice),
+                    None
+                ),
+                Err(Error::ApprovalRequired)
+            );
+            assert_eq!(asset.destroy_asset(), Err(Error::ApprovalRequired));
+            assert_eq!(asset.set_reconfig_delay(0), Err(Error::ApprovalRequired));
+
+            let action_id = asset
+                .propose_action(PendingAction::Modify {
+                    manager: Some(accounts.alice),
+                    reserve: Some(accounts.alice),
+                    freeze: Some(accounts.alice),
+                    clawback: Some(AccountId::from([0x0; 32])),
+                })
+                .unwrap();
+            assert_eq!(asset.clawback_id(), accounts.alice);
+            advance_blocks(2);
+            assert_eq!(asset.execute_action(action_id), Err(Error::TimelockActive));
+
+            advance_blocks(1);
+            set_caller(accounts.bob);
+            assert_eq!(asset.execute_action(action_id), Err(Error::NotManagerId));
+            set_caller(accounts.alice);
+            asset.execute_action(action_id).unwrap();
             assert_eq!(asset.clawback_id(), AccountId::from([0x0; 32]));
+            assert_eq!(asset.execute_action(action_id), Err(Error::NoSuchProposal));
         }
 
-        // Test if constructor works with default values
+        // Test if freezing the reserve is timelocked and pending actions can be cancelled
         #[ink::test]
-        fn constructor_works_with_default_values() {
-            let asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn timelocked_reserve_freeze_can_be_cancelled() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.set_reconfig_delay(3).unwrap();
+
+            // other accounts aren't timelocked
+            asset.freeze(accounts.bob, true, 0).unwrap();
+            assert_eq!(
+                asset.freeze(accounts.alice, true, 0),
+                Err(Error::ApprovalRequired)
+            );
+            assert_eq!(
+                asset.freeze_many(ink_prelude::vec![accounts.alice], true),
+                Err(Error::ApprovalRequired)
             );
 
-            assert_eq!(asset.asset_name(), "Test subsa");
-            assert_eq!(asset.unit_name(), "TSSA");
-            assert_eq!(asset.total(), 1000);
-            assert_eq!(asset.decimals(), 10);
-            assert_eq!(asset.default_frozen(), true);
-            assert_eq!(asset.url(), "www.test.com");
-            assert_eq!(asset.manager_id(), AccountId::from([0x0; 32]));
-            assert_eq!(asset.reserve_id(), AccountId::from([0x1; 32]));
+            let cancelled = asset
+                .propose_action(PendingAction::FreezeReserve { freeze: true })
+                .unwrap();
+            let action_id = asset
+                .propose_action(PendingAction::FreezeReserve { freeze: true })
+                .unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(asset.cancel_pending(cancelled), Err(Error::NotManagerId));
+            set_caller(accounts.alice);
+            asset.cancel_pending(cancelled).unwrap();
+            assert_eq!(asset.cancel_pending(cancelled), Err(Error::NoSuchProposal));
+
+            advance_blocks(3);
+            assert_eq!(asset.execute_action(cancelled), Err(Error::NoSuchProposal));
+            asset.execute_action(action_id).unwrap();
+            assert!(asset.is_frozen(accounts.alice));
+        }
+
+        // Test if only the freeze authority can freeze the reserve through a proposal
+        #[ink::test]
+        fn reserve_freeze_proposal_requires_freeze_authority() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                freeze: Some(accounts.eve),
+                ..default_params()
+            });
+            asset.set_reconfig_delay(3).unwrap();
+            assert_eq!(
+                asset.propose_action(PendingAction::FreezeReserve { freeze: true }),
+                Err(Error::NotFreezeId)
+            );
+
+            set_caller(accounts.eve);
+            let action_id = asset
+                .propose_action(PendingAction::FreezeReserve { freeze: true })
+                .unwrap();
+            advance_blocks(3);
+            set_caller(accounts.alice);
+            assert_eq!(asset.execute_action(action_id), Err(Error::NotFreezeId));
+            set_caller(accounts.eve);
+            asset.execute_action(action_id).unwrap();
+            assert!(asset.is_frozen(accounts.alice));
+
+            // a non-freezable asset rejects the proposal
+            set_caller(accounts.alice);
+            let mut asset = Subsa::new(AssetParams {
+                freezable: false,
+                ..default_params()
+            });
+            asset.set_reconfig_delay(3).unwrap();
+            assert_eq!(
+                asset.propose_action(PendingAction::FreezeReserve { freeze: true }),
+                Err(Error::NotFreezable)
+            );
+        }
+
+        // Test if delegating to a controller is timelocked like the other reconfigurations
+        #[ink::test]
+        fn timelocked_delegation_waits_for_delay() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.set_reconfig_delay(3).unwrap();
+            assert_eq!(
+                asset.delegate_to(Some(accounts.eve)),
+                Err(Error::ApprovalRequired)
+            );
+
+            let action_id = asset
+                .propose_action(PendingAction::DelegateTo {
+                    controller: Some(accounts.eve),
+                })
+                .unwrap();
+            assert_eq!(asset.controller(), None);
+            advance_blocks(3);
+            asset.execute_action(action_id).unwrap();
+            assert_eq!(asset.controller(), Some(accounts.eve));
+        }
+
+        // Test if granting a role needs the approval of the co-managers
+        #[ink::test]
+        fn grant_role_requires_approval() {
+            let accounts = default_accounts();
+            let mut asset = create_multi_authority_asset();
+            assert_eq!(
+                asset.grant_role(RoleKind::Freeze, accounts.eve),
+                Err(Error::ApprovalRequired)
+            );
+
+            let action_id = asset
+                .propose_action(PendingAction::GrantRole {
+                    role: RoleKind::Freeze,
+                    account: accounts.eve,
+                })
+                .unwrap();
+            assert!(!asset.has_role(RoleKind::Freeze, accounts.eve));
+            set_caller(accounts.bob);
+            asset.approve_action(action_id).unwrap();
+            assert!(asset.has_role(RoleKind::Freeze, accounts.eve));
+        }
+
+        // Test if modify_asset keeps the authorities passed as None
+        #[ink::test]
+        fn modify_asset_none_keeps_current_values() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset
+                .modify_asset(None, Some(accounts.bob), None, None)
+                .unwrap();
+            assert_eq!(asset.manager_id(), accounts.alice);
+            assert_eq!(asset.reserve_id(), accounts.bob);
+            assert_eq!(asset.freeze_id(), accounts.alice);
+            assert_eq!(asset.clawback_id(), accounts.alice);
+
+            asset
+                .modify_asset(None, None, Some(AccountId::from([0x0; 32])), None)
+                .unwrap();
             assert_eq!(asset.freeze_id(), AccountId::from([0x0; 32]));
-            assert_eq!(asset.clawback_id(), AccountId::from([0x0; 32]));
-            assert_eq!(asset.balances.get(&asset.reserve_id()).unwrap_or(0), 1000);
+            // a zeroed address can still be set again
+            asset
+                .modify_asset(None, None, Some(accounts.charlie), None)
+                .unwrap();
+            assert_eq!(asset.freeze_id(), accounts.charlie);
         }
 
-        // Test if asset_id field is set correctly in constructor to the contract address
+        // Test if clear_role zeroes an authority address for good, including its grants
         #[ink::test]
-        fn constructor_sets_asset_id() {
-            use ink_lang::codegen::Env;
-            let asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn clear_role_is_permanent() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            asset.grant_role(RoleKind::Reserve, accounts.bob).unwrap();
+            assert_eq!(
+                asset.clear_role(RoleKind::Custom(1)),
+                Err(Error::InvalidRole)
             );
-            assert_eq!(asset.asset_id(), asset.env().account_id());
+            set_caller(accounts.bob);
+            assert_eq!(
+                asset.clear_role(RoleKind::Reserve),
+                Err(Error::NotManagerId)
+            );
+
+            set_caller(accounts.alice);
+            asset.clear_role(RoleKind::Reserve).unwrap();
+            assert!(asset.reserve_renounced());
+            assert_eq!(asset.reserve_id(), AccountId::from([0x0; 32]));
+            assert!(!asset.is_authorized(accounts.bob, RoleKind::Reserve));
+            assert_eq!(
+                asset.clear_role(RoleKind::Reserve),
+                Err(Error::ReserveRenounced)
+            );
+            assert_eq!(
+                asset.grant_role(RoleKind::Reserve, accounts.charlie),
+                Err(Error::ReserveRenounced)
+            );
+            assert_eq!(
+                asset.modify_asset(None, Some(accounts.alice), None, None),
+                Err(Error::ReserveRenounced)
+            );
+
+            let cleared = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::RoleCleared(RoleCleared { role, previous, .. })) => {
+                            Some((role, previous))
+                        }
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(cleared, vec![(RoleKind::Reserve, accounts.alice)]);
+
+            asset.clear_role(RoleKind::Manager).unwrap();
+            assert!(asset.management_renounced());
+            assert_eq!(asset.clear_role(RoleKind::Freeze), Err(Error::NotManagerId));
         }
 
-        // check if Create event is emitted in constructor
+        // Test if clearing a role goes through a proposal with co-managers
         #[ink::test]
-        fn constructor_emits_create_event() {
-            let asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn multi_authority_clear_role_requires_approval() {
+            let accounts = default_accounts();
+            let mut asset = create_multi_authority_asset();
+            assert_eq!(
+                asset.clear_role(RoleKind::Clawback),
+                Err(Error::ApprovalRequired)
             );
-            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(events.len(), 1);
-            let event = &events[0];
-            assert_eq!(event.topics.len(), 5);
-            // TODO TEST EVENT WITH Event as scale:Decode
+
+            let action_id = asset
+                .propose_action(PendingAction::ClearRole {
+                    role: RoleKind::Clawback,
+                })
+                .unwrap();
+            set_caller(accounts.bob);
+            asset.approve_action(action_id).unwrap();
+            assert!(asset.clawback_renounced());
+            assert_eq!(asset.clawback_id(), AccountId::from([0x0; 32]));
         }
 
-        // Test if optIn works
+        // Test if amounts are emitted as event data instead of topics
         #[ink::test]
-        fn opt_in_works() {
-            // set caller
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
-            let mut asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn transfer_amount_is_not_a_topic() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+
+            let event = ink_env::test::recorded_events().last().unwrap();
+            // event signature, sender, receiver and asset id
+            assert_eq!(event.topics.len(), 4);
+            match <Event as Decode>::decode(&mut &event.data[..]) {
+                Ok(Event::Transfer(Transfer { amount, .. })) => assert_eq!(amount, 100),
+                _ => panic!("expected a Transfer event"),
+            }
+        }
+
+        // Test if transfer_with_note attaches the note to the Transfer event
+        #[ink::test]
+        fn transfer_with_note_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(
+                asset.transfer_with_note(accounts.bob, 100, vec![0x1; MAX_NOTE_LEN + 1]),
+                Err(Error::NoteTooLong)
             );
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
-            asset.opt_in();
-            // check if caller account is opted in in accounts_opted_in map
+            asset
+                .transfer_with_note(accounts.bob, 100, b"deposit-42".to_vec())
+                .unwrap();
+            asset.transfer(accounts.bob, 50).unwrap();
+            assert_eq!(asset.balance_of(accounts.bob), Ok(150));
+
+            let notes = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Transfer(Transfer { amount, note, .. })) => Some((amount, note)),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(notes, vec![(100, b"deposit-42".to_vec()), (50, Vec::new())]);
+        }
+
+        // Test if holder_count tracks the accounts with a non-zero balance
+        #[ink::test]
+        fn holder_count_tracks_non_zero_balances() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(asset.holder_count(), 1);
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            assert_eq!(asset.opted_in_count(), 3);
+            assert_eq!(asset.holder_count(), 1);
+
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 100).unwrap();
+            asset.transfer(accounts.charlie, 100).unwrap();
+            assert_eq!(asset.holder_count(), 3);
+            assert_eq!(asset.circulating_supply(), 200);
+
+            set_caller(accounts.bob);
+            asset.transfer(accounts.charlie, 100).unwrap();
+            assert_eq!(asset.holder_count(), 2);
+            asset.opt_out().unwrap();
+            assert_eq!(asset.holder_count(), 2);
+            assert_eq!(asset.verify_invariants(), Ok(()));
+        }
+
+        // Test if holders pages through the opted in accounts and their balances
+        #[ink::test]
+        fn holders_paginates_opted_in_accounts() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            for holder in [accounts.bob, accounts.charlie, accounts.django] {
+                set_caller(holder);
+                asset.opt_in().unwrap();
+            }
+            set_caller(accounts.alice);
+            asset.transfer(accounts.charlie, 100).unwrap();
+
             assert_eq!(
-                asset.accounts_opted_in.get(&AccountId::from([0x1; 32])),
-                Some(true)
+                asset.holders(0, 2),
+                vec![(accounts.alice, 900), (accounts.bob, 0)]
+            );
+            assert_eq!(
+                asset.holders(2, 10),
+                vec![(accounts.charlie, 100), (accounts.django, 0)]
+            );
+            assert_eq!(asset.holders(4, 10), vec![]);
+
+            // the last account takes the place of the one opting out
+            set_caller(accounts.bob);
+            asset.opt_out().unwrap();
+            assert_eq!(
+                asset.holders(0, 10),
+                vec![
+                    (accounts.alice, 900),
+                    (accounts.django, 0),
+                    (accounts.charlie, 100)
+                ]
             );
+            asset.opt_in().unwrap();
+            assert_eq!(asset.holders(3, 10), vec![(accounts.bob, 0)]);
         }
 
-        // Test if optIn emits OptIn event
+        // Test if opting out an account that was never indexed leaves the index intact
         #[ink::test]
-        fn opt_in_emits_opt_in_event() {
-            // set caller
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
-            let mut asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn opt_out_of_unindexed_account_keeps_index() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+
+            asset.opt_out_account(accounts.charlie);
+            assert_eq!(asset.opted_in_count(), 2);
+            assert_eq!(
+                asset.holders(0, 10),
+                vec![(accounts.alice, 1000), (accounts.bob, 0)]
             );
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
-            asset.opt_in();
-            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(events.len(), 2);
-            let event = &events[1];
-            assert_eq!(event.topics.len(), 3);
-            // TODO TEST EVENT WITH Event as scale:Decode
         }
 
-        // Test if optOut works
+        // Test if the issued supply is recorded with each snapshot
         #[ink::test]
-        fn opt_out_works() {
-            // set caller
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
-            let mut asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn total_supply_at_works() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            assert_eq!(asset.total_supply_at(1), Err(Error::InvalidSnapshot));
+            asset.burn(200).unwrap();
+            let snapshot_id = asset.take_snapshot().unwrap();
+            asset.burn(100).unwrap();
+            assert_eq!(asset.total_supply_at(snapshot_id), Ok(800));
+            assert_eq!(asset.balance_of_at(accounts.alice, snapshot_id), Ok(800));
+            assert_eq!(asset.total_supply(), 700);
+        }
+
+        // Test if holders claim native dividends pro rata to their balance at the snapshot
+        #[ink::test]
+        fn dividends_are_claimed_pro_rata() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 250).unwrap();
+
+            assert_eq!(asset.deposit_dividend(), Err(Error::ZeroAmount));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(400);
+            set_caller(accounts.bob);
+            assert_eq!(asset.deposit_dividend(), Err(Error::NotManagerId));
+            set_caller(accounts.alice);
+            let dividend_id = asset.deposit_dividend().unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(0);
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                400,
             );
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
-            asset.opt_in();
-            asset.opt_out();
-            // check if caller account is opted in in accounts_opted_in map
+            assert_eq!(asset.dividend(dividend_id), Some((1, 400)));
+
+            // transfers after the deposit don't change the shares
+            asset.transfer(accounts.bob, 250).unwrap();
+            assert_eq!(asset.dividend_of(dividend_id, accounts.alice), Ok(300));
+            assert_eq!(asset.dividend_of(dividend_id, accounts.bob), Ok(100));
             assert_eq!(
-                asset.accounts_opted_in.get(&AccountId::from([0x1; 32])),
-                Some(false)
+                asset.dividend_of(2, accounts.bob),
+                Err(Error::NoSuchDividend)
+            );
+
+            set_caller(accounts.bob);
+            let before =
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.bob)
+                    .unwrap();
+            assert_eq!(asset.claim_dividend(dividend_id), Ok(100));
+            assert!(asset.dividend_claimed(dividend_id, accounts.bob));
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.bob),
+                Ok(before + 100)
+            );
+            assert_eq!(
+                asset.claim_dividend(dividend_id),
+                Err(Error::AlreadyClaimed)
             );
+
+            set_caller(accounts.charlie);
+            assert_eq!(asset.claim_dividend(dividend_id), Err(Error::ZeroAmount));
         }
 
-        // Test if optOut emits OptOut event
+        // Test if a dividend share is paid to the payout address of the holder
         #[ink::test]
-        fn opt_out_emits_opt_out_event() {
-            // set caller
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x0; 32]));
-            let mut asset = Subsa::new(
-                "Test subsa".into(),
-                "TSSA".into(),
-                1000,
-                10,
-                true,
-                "www.test.com".into(),
-                [0x0; 4],
-                None,
-                None,
-                None,
-                None,
+        fn dividend_claim_pays_payout_address() {
+            let accounts = default_accounts();
+            let mut asset = create_asset();
+            set_caller(accounts.bob);
+            asset.opt_in().unwrap();
+            asset.set_payout_address(Some(accounts.django)).unwrap();
+            set_caller(accounts.alice);
+            asset.transfer(accounts.bob, 500).unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(400);
+            let dividend_id = asset.deposit_dividend().unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(0);
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                400,
             );
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
-            asset.opt_in();
-            asset.opt_out();
-            let events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(events.len(), 3);
-            let event = &events[2];
-            assert_eq!(event.topics.len(), 3);
-            // TODO TEST EVENT WITH Event as scale:Decode
+
+            let balance = |account| {
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account).unwrap()
+            };
+            let (bob_before, django_before) = (balance(accounts.bob), balance(accounts.django));
+            set_caller(accounts.bob);
+            assert_eq!(asset.claim_dividend(dividend_id), Ok(200));
+            assert_eq!(balance(accounts.bob), bob_before);
+            assert_eq!(balance(accounts.django), django_before + 200);
         }
     }
 }